@@ -0,0 +1,63 @@
+use io::{div_ix, h2i_ix, ix, rem_ix, sub_ix, zero_ix};
+
+fn n(v: i64) -> ix {
+    ix::from(v)
+}
+
+#[test]
+fn division_truncates_towards_zero() {
+    assert_eq!(div_ix(&n(-7), &n(2)), n(-3));
+    assert_eq!(rem_ix(&n(-7), &n(2)), n(-1));
+    for (a, b, q, r) in [
+        (7, 2, 3, 1),
+        (7, -2, -3, 1),
+        (-7, -2, 3, -1),
+        (-6, 2, -3, 0),
+        (0, -5, 0, 0),
+    ] {
+        assert_eq!(div_ix(&n(a), &n(b)), n(q), "{} / {}", a, b);
+        assert_eq!(rem_ix(&n(a), &n(b)), n(r), "{} % {}", a, b);
+    }
+    // bignum's own versions floor; the wrappers must not.
+    assert_eq!(bignum::div_ix(&n(-7), &n(2)), n(-4));
+    assert_eq!(bignum::rem_ix(&n(-7), &n(2)), n(1));
+}
+
+#[test]
+#[should_panic]
+fn division_by_zero_panics() {
+    div_ix(&n(1), &zero_ix());
+}
+
+#[test]
+fn h2i_ix_edge_cases() {
+    for (s, want) in [
+        ("", 0),
+        ("   ", 0),
+        ("-", 0),
+        ("0x", 0),
+        ("-0x", 0),
+        ("+5", 5),
+        ("-+5", -5),
+        ("0X1f", 31),
+        ("-ff", -255),
+        ("-0", 0),
+    ] {
+        assert_eq!(h2i_ix(s), n(want), "{:?}", s);
+    }
+    assert!(!h2i_ix("-0").sign);
+    assert_eq!(h2i_ix("10000000000000000"), ix::from(1u128 << 64));
+}
+
+#[test]
+#[should_panic(expected = "bad hex input")]
+fn h2i_ix_panics_on_bad_digits() {
+    h2i_ix("12g4");
+}
+
+#[test]
+fn zero_minus_zero_is_not_negative() {
+    let d = sub_ix(&zero_ix(), &zero_ix());
+    assert!(!d.sign);
+    assert_eq!(d, zero_ix());
+}
@@ -0,0 +1,77 @@
+// Compare Knuth D (`div_rem_mag`) with the shift-subtract loop it replaced.
+// Run with `cargo bench --bench div`.
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use bignum::{add_bit_to_vec, bit_len, cmp_mag, div_rem_mag, shl_mag, sub_mag};
+
+/// The previous implementation: one shift and one subtraction per quotient bit.
+fn div_rem_shift_subtract(a_vals: &[u64], b_vals: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    if cmp_mag(a_vals, b_vals) < 0 {
+        return (vec![], a_vals.to_vec());
+    }
+    let mut rem = a_vals.to_vec();
+    let mut q: Vec<u64> = vec![];
+    let mut shift = bit_len(a_vals) as isize - bit_len(b_vals) as isize;
+    while shift >= 0 {
+        let t = shl_mag(b_vals, shift as usize);
+        if cmp_mag(&t, &rem) <= 0 {
+            rem = sub_mag(&rem, &t);
+            add_bit_to_vec(&mut q, shift as usize);
+        }
+        shift -= 1;
+    }
+    while q.last() == Some(&0) {
+        q.pop();
+    }
+    (q, rem)
+}
+
+fn limbs(n: usize, seed: &mut u64) -> Vec<u64> {
+    (0..n)
+        .map(|_| {
+            *seed ^= *seed << 13;
+            *seed ^= *seed >> 7;
+            *seed ^= *seed << 17;
+            *seed | 1
+        })
+        .collect()
+}
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let mut iters = 1u32;
+    loop {
+        let start = Instant::now();
+        for _ in 0..iters {
+            f();
+        }
+        let elapsed = start.elapsed();
+        if elapsed > Duration::from_millis(200) {
+            return elapsed / iters;
+        }
+        iters *= 2;
+    }
+}
+
+fn main() {
+    let mut seed = 0x9e37_79b9_7f4a_7c15;
+    println!("{:>16} {:>14} {:>14} {:>8}", "bits", "shift-sub", "knuth-d", "speedup");
+    for &(a_bits, b_bits) in &[(512, 256), (1024, 512), (2048, 1024), (4096, 2048), (4096, 4032), (8192, 4096)] {
+        let a = limbs(a_bits / 64, &mut seed);
+        let b = limbs(b_bits / 64, &mut seed);
+        assert_eq!(div_rem_mag(&a, &b), div_rem_shift_subtract(&a, &b));
+        let old = time(|| {
+            black_box(div_rem_shift_subtract(black_box(&a), black_box(&b)));
+        });
+        let new = time(|| {
+            black_box(div_rem_mag(black_box(&a), black_box(&b)));
+        });
+        println!(
+            "{:>16} {:>14?} {:>14?} {:>7.1}x",
+            format!("{}/{}", a_bits, b_bits),
+            old,
+            new,
+            old.as_secs_f64() / new.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,51 @@
+// Compare `mul_mag` / `sqr_mag` with plain schoolbook multiplication, to
+// tune KARATSUBA_THRESHOLD and TOOM3_THRESHOLD. Run with `cargo bench --bench mul`.
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+use bignum::{mul_mag, mul_schoolbook, sqr_mag};
+
+fn limbs(n: usize, seed: &mut u64) -> Vec<u64> {
+    (0..n)
+        .map(|_| {
+            *seed ^= *seed << 13;
+            *seed ^= *seed >> 7;
+            *seed ^= *seed << 17;
+            *seed | 1
+        })
+        .collect()
+}
+
+fn time<F: FnMut()>(mut f: F) -> Duration {
+    let mut iters = 1u32;
+    loop {
+        let start = Instant::now();
+        for _ in 0..iters {
+            f();
+        }
+        let elapsed = start.elapsed();
+        if elapsed > Duration::from_millis(200) {
+            return elapsed / iters;
+        }
+        iters *= 2;
+    }
+}
+
+fn main() {
+    let mut seed = 0x9e37_79b9_7f4a_7c15;
+    println!("{:>8} {:>14} {:>14} {:>14}", "limbs", "schoolbook", "mul_mag", "sqr_mag");
+    for &n in &[16, 24, 32, 48, 64, 128, 192, 256, 512, 1024, 2048] {
+        let a = limbs(n, &mut seed);
+        let b = limbs(n, &mut seed);
+        let school = time(|| {
+            black_box(mul_schoolbook(black_box(&a), black_box(&b)));
+        });
+        let fast = time(|| {
+            black_box(mul_mag(black_box(&a), black_box(&b)));
+        });
+        let sqr = time(|| {
+            black_box(sqr_mag(black_box(&a)));
+        });
+        println!("{:>8} {:>14?} {:>14?} {:>14?}", n, school, fast, sqr);
+    }
+}
@@ -0,0 +1,101 @@
+use crate::modular::window_pow;
+use crate::{
+    div_rem_mag, div_rem_reciprocal, ix, mul_mag, reciprocal_mag, rem_ix, sqr_mag, sub_ix,
+};
+
+/// Precomputed state for Barrett reduction modulo a fixed positive `n`.
+///
+/// Unlike Montgomery form, values stay ordinary residues, so this suits
+/// moduli that are even or code that reduces only a few products.
+#[derive(Clone, Debug)]
+pub struct BarrettCtx {
+    n: Vec<u64>,
+    /// `floor(2^(128·k) / n)` for a `k`-limb modulus.
+    mu: Vec<u64>,
+}
+
+impl BarrettCtx {
+    /// Build a context for `modulus`, or `None` unless it is positive.
+    pub fn new(modulus: &ix) -> Option<BarrettCtx> {
+        if modulus.sign || modulus.vals.is_empty() {
+            return None;
+        }
+        Some(BarrettCtx::from_mag(modulus.vals.clone()))
+    }
+
+    /// Context for a nonzero, normalized magnitude.
+    pub(crate) fn from_mag(n: Vec<u64>) -> BarrettCtx {
+        let mu = reciprocal_mag(&n);
+        BarrettCtx { n, mu }
+    }
+
+    pub fn modulus(&self) -> ix {
+        ix {
+            sign: false,
+            vals: self.n.clone(),
+        }
+    }
+
+    pub(crate) fn modulus_mag(&self) -> &[u64] {
+        &self.n
+    }
+
+    /// Quotient and remainder of a magnitude `x`. Inputs below
+    /// `2^(128·k)` take two multiplications; larger ones fall back to long
+    /// division.
+    pub(crate) fn div_rem_mag(&self, x: &[u64]) -> (Vec<u64>, Vec<u64>) {
+        if x.len() > 2 * self.n.len() {
+            return div_rem_mag(x, &self.n);
+        }
+        div_rem_reciprocal(x, &self.n, &self.mu)
+    }
+
+    fn reduce_mag(&self, x: &[u64]) -> Vec<u64> {
+        self.div_rem_mag(x).1
+    }
+
+    /// `x mod n` in `[0, n)`, for any integer `x`.
+    pub fn reduce(&self, x: &ix) -> ix {
+        let r = ix {
+            sign: false,
+            vals: self.reduce_mag(&x.vals),
+        };
+        if x.sign && !r.vals.is_empty() {
+            sub_ix(&self.modulus(), &r)
+        } else {
+            r
+        }
+    }
+
+    /// `a·b mod n` for residues `a`, `b` in `[0, n)`.
+    pub fn mul(&self, a: &ix, b: &ix) -> ix {
+        ix {
+            sign: false,
+            vals: self.reduce_mag(&mul_mag(&a.vals, &b.vals)),
+        }
+    }
+
+    /// `a² mod n` for a residue `a` in `[0, n)`.
+    pub fn sqr(&self, a: &ix) -> ix {
+        ix {
+            sign: false,
+            vals: self.reduce_mag(&sqr_mag(&a.vals)),
+        }
+    }
+
+    /// `base^exp mod n` for any `base` and non-negative `exp`.
+    pub fn pow(&self, base: &ix, exp: &ix) -> ix {
+        assert!(!exp.sign, "negative exponent");
+        let one = self.reduce(&ix {
+            sign: false,
+            vals: vec![1],
+        });
+        window_pow(
+            &rem_ix(base, &self.modulus()),
+            &exp.vals,
+            one,
+            |a, b| self.mul(a, b),
+            |a| self.sqr(a),
+        )
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{add_mag, bit_len, ix, normalize_vals, shl_mag, shr_mag, sub_mag, zero_ix};
+
+// Bitwise operations treat `ix` as an infinitely sign-extended two's
+// complement number, the way Python ints behave.
+
+/// `x` as `n` two's-complement limbs; `n` must leave room for the sign bit.
+pub(crate) fn to_twos(x: &ix, n: usize) -> Vec<u64> {
+    let mut out = if x.sign {
+        sub_mag(&x.vals, &[1])
+    } else {
+        x.vals.clone()
+    };
+    out.resize(n, 0);
+    if x.sign {
+        for limb in out.iter_mut() {
+            *limb = !*limb;
+        }
+    }
+    out
+}
+
+pub(crate) fn from_twos(mut limbs: Vec<u64>, negative: bool) -> ix {
+    if negative {
+        for limb in limbs.iter_mut() {
+            *limb = !*limb;
+        }
+        normalize_vals(&mut limbs);
+        return ix {
+            sign: true,
+            vals: add_mag(&limbs, &[1]),
+        };
+    }
+    normalize_vals(&mut limbs);
+    ix {
+        sign: false,
+        vals: limbs,
+    }
+}
+
+fn bitwise(a: &ix, b: &ix, op: impl Fn(u64, u64) -> u64) -> ix {
+    let n = std::cmp::max(a.vals.len(), b.vals.len()) + 1;
+    let (ta, tb) = (to_twos(a, n), to_twos(b, n));
+    let limbs = ta.iter().zip(&tb).map(|(&x, &y)| op(x, y)).collect();
+    let negative = op(a.sign as u64, b.sign as u64) & 1 == 1;
+    from_twos(limbs, negative)
+}
+
+pub fn and_ix(a: &ix, b: &ix) -> ix {
+    bitwise(a, b, |x, y| x & y)
+}
+
+pub fn or_ix(a: &ix, b: &ix) -> ix {
+    bitwise(a, b, |x, y| x | y)
+}
+
+pub fn xor_ix(a: &ix, b: &ix) -> ix {
+    bitwise(a, b, |x, y| x ^ y)
+}
+
+/// `!x == -x - 1`.
+pub fn not_ix(x: &ix) -> ix {
+    if x.sign {
+        let mut r = ix {
+            sign: false,
+            vals: sub_mag(&x.vals, &[1]),
+        };
+        normalize_vals(&mut r.vals);
+        r
+    } else {
+        ix {
+            sign: true,
+            vals: add_mag(&x.vals, &[1]),
+        }
+    }
+}
+
+/// `x * 2^k`.
+pub fn shl_ix(x: &ix, k: usize) -> ix {
+    ix {
+        sign: x.sign,
+        vals: shl_mag(&x.vals, k),
+    }
+}
+
+/// `floor(x / 2^k)`, so negative values round towards negative infinity.
+pub fn shr_ix(x: &ix, k: usize) -> ix {
+    let q = shr_mag(&x.vals, k);
+    if !x.sign {
+        return ix {
+            sign: false,
+            vals: q,
+        };
+    }
+    let exact = x.trailing_zeros().is_some_and(|tz| tz >= k);
+    let vals = if exact { q } else { add_mag(&q, &[1]) };
+    if vals.is_empty() {
+        return zero_ix();
+    }
+    ix { sign: true, vals }
+}
+
+impl ix {
+    /// Bits needed for `|self|`, like Python's `int.bit_length()`.
+    pub fn bit_len(&self) -> usize {
+        bit_len(&self.vals)
+    }
+
+    /// Index of the lowest set bit, or `None` for zero. The same for `x`
+    /// and `-x`.
+    pub fn trailing_zeros(&self) -> Option<usize> {
+        let i = self.vals.iter().position(|&v| v != 0)?;
+        Some(i * 64 + self.vals[i].trailing_zeros() as usize)
+    }
+
+    /// Set bits in `|self|`, like Python's `int.bit_count()`.
+    pub fn count_ones(&self) -> u64 {
+        self.vals.iter().map(|v| v.count_ones() as u64).sum()
+    }
+
+    /// Bit `k` of the two's-complement form: `(self >> k) & 1 == 1`.
+    pub fn test_bit(&self, k: usize) -> bool {
+        let limb = k / 64;
+        if !self.sign {
+            return self.vals.get(limb).is_some_and(|v| v >> (k % 64) & 1 == 1);
+        }
+        let twos = to_twos(self, std::cmp::max(self.vals.len(), limb) + 1);
+        twos[limb] >> (k % 64) & 1 == 1
+    }
+
+    /// Set (`value = true`) or clear bit `k` of the two's-complement form.
+    pub fn set_bit(&mut self, k: usize, value: bool) {
+        if self.test_bit(k) == value {
+            return;
+        }
+        let n = std::cmp::max(self.vals.len(), k / 64 + 1) + 1;
+        let mut twos = to_twos(self, n);
+        twos[k / 64] ^= 1 << (k % 64);
+        *self = from_twos(twos, self.sign);
+    }
+}
@@ -0,0 +1,128 @@
+use std::fmt;
+
+use crate::bits::{from_twos, to_twos};
+use crate::{ix, normalize_vals};
+
+/// Error returned when an `ix` does not fit in a fixed-width byte buffer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BytesOverflowError {
+    /// Bytes the value actually needs.
+    pub needed: usize,
+    /// Bytes that were requested.
+    pub width: usize,
+}
+
+impl fmt::Display for BytesOverflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ix needs {} bytes but only {} were allowed",
+            self.needed, self.width
+        )
+    }
+}
+
+impl std::error::Error for BytesOverflowError {}
+
+fn limbs_from_le(bytes: &[u8], fill: u8) -> Vec<u64> {
+    bytes
+        .chunks(8)
+        .map(|chunk| {
+            let mut buf = [fill; 8];
+            buf[..chunk.len()].copy_from_slice(chunk);
+            u64::from_le_bytes(buf)
+        })
+        .collect()
+}
+
+fn limbs_to_le(limbs: &[u64]) -> Vec<u8> {
+    limbs.iter().flat_map(|v| v.to_le_bytes()).collect()
+}
+
+// The unsigned encodings carry only the magnitude; callers that need the
+// sign read `x.sign` or use the two's-complement `*_signed_*` forms.
+impl ix {
+    /// Non-negative `ix` from little-endian magnitude bytes.
+    pub fn from_bytes_le(bytes: &[u8]) -> ix {
+        let mut vals = limbs_from_le(bytes, 0);
+        normalize_vals(&mut vals);
+        ix { sign: false, vals }
+    }
+
+    /// Non-negative `ix` from big-endian magnitude bytes.
+    pub fn from_bytes_be(bytes: &[u8]) -> ix {
+        let le: Vec<u8> = bytes.iter().rev().copied().collect();
+        ix::from_bytes_le(&le)
+    }
+
+    /// Minimal little-endian bytes of `|self|`; zero encodes as `[0]`.
+    pub fn to_bytes_le(&self) -> Vec<u8> {
+        let mut out = limbs_to_le(&self.vals);
+        while out.len() > 1 && out.last() == Some(&0) {
+            out.pop();
+        }
+        if out.is_empty() {
+            out.push(0);
+        }
+        out
+    }
+
+    /// Minimal big-endian bytes of `|self|`; zero encodes as `[0]`.
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        let mut out = self.to_bytes_le();
+        out.reverse();
+        out
+    }
+
+    /// `|self|` as exactly `width` little-endian bytes, zero-padded.
+    pub fn to_bytes_le_padded(&self, width: usize) -> Result<Vec<u8>, BytesOverflowError> {
+        let mut out = self.to_bytes_le();
+        let needed = if self.vals.is_empty() { 0 } else { out.len() };
+        if needed > width {
+            return Err(BytesOverflowError { needed, width });
+        }
+        out.resize(width, 0);
+        Ok(out)
+    }
+
+    /// `|self|` as exactly `width` big-endian bytes, zero-padded.
+    pub fn to_bytes_be_padded(&self, width: usize) -> Result<Vec<u8>, BytesOverflowError> {
+        let mut out = self.to_bytes_le_padded(width)?;
+        out.reverse();
+        Ok(out)
+    }
+
+    /// `ix` from little-endian two's-complement bytes; the top bit of the
+    /// last byte is the sign. Empty input is zero.
+    pub fn from_signed_bytes_le(bytes: &[u8]) -> ix {
+        let negative = bytes.last().is_some_and(|&b| b & 0x80 != 0);
+        let limbs = limbs_from_le(bytes, if negative { 0xff } else { 0 });
+        from_twos(limbs, negative)
+    }
+
+    /// `ix` from big-endian two's-complement bytes.
+    pub fn from_signed_bytes_be(bytes: &[u8]) -> ix {
+        let le: Vec<u8> = bytes.iter().rev().copied().collect();
+        ix::from_signed_bytes_le(&le)
+    }
+
+    /// Shortest little-endian two's-complement encoding of `self`.
+    pub fn to_signed_bytes_le(&self) -> Vec<u8> {
+        let mut out = limbs_to_le(&to_twos(self, self.vals.len() + 1));
+        let fill = if self.sign { 0xff } else { 0 };
+        while out.len() > 1
+            && out[out.len() - 1] == fill
+            && (out[out.len() - 2] & 0x80 != 0) == self.sign
+        {
+            out.pop();
+        }
+        out
+    }
+
+    /// Shortest big-endian two's-complement encoding of `self`.
+    pub fn to_signed_bytes_be(&self) -> Vec<u8> {
+        let mut out = self.to_signed_bytes_le();
+        out.reverse();
+        out
+    }
+}
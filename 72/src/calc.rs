@@ -0,0 +1,496 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::{IxError, ix, zero_ix};
+
+// Grammar, loosest binding first, following Python's integer operators:
+//
+//   stmt    := IDENT '=' expr | expr
+//   expr    := xor ('|' xor)*
+//   xor     := and ('^' and)*
+//   and     := shift ('&' shift)*
+//   shift   := sum (('<<' | '>>') sum)*
+//   sum     := term (('+' | '-') term)*
+//   term    := unary (('*' | '/' | '//' | '%') unary)*
+//   unary   := ('-' | '+' | '~') unary | power
+//   power   := primary ('**' unary)?
+//   primary := NUMBER | IDENT | IDENT '(' args ')' | '(' expr ')'
+//
+// `/` and `//` both floor, and `%` takes the sign of the divisor.
+
+/// Error from evaluating one calculator statement.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CalcError {
+    /// The line could not be tokenized or parsed.
+    Syntax(String),
+    /// A variable was read before being assigned.
+    UndefinedVariable(String),
+    /// A call named a function that does not exist or passed the wrong
+    /// number of arguments.
+    BadCall(String),
+    /// An argument was outside the operation's domain, such as a negative
+    /// exponent or an even root of a negative number.
+    Domain(String),
+    /// Division or reduction by zero.
+    Arith(IxError),
+}
+
+impl fmt::Display for CalcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CalcError::Syntax(msg) => write!(f, "syntax error: {}", msg),
+            CalcError::UndefinedVariable(name) => write!(f, "undefined variable {:?}", name),
+            CalcError::BadCall(msg) => f.write_str(msg),
+            CalcError::Domain(msg) => f.write_str(msg),
+            CalcError::Arith(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for CalcError {}
+
+impl From<IxError> for CalcError {
+    fn from(e: IxError) -> CalcError {
+        CalcError::Arith(e)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Tok {
+    Num(ix),
+    Ident(String),
+    Op(&'static str),
+}
+
+impl fmt::Display for Tok {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Tok::Num(n) => write!(f, "{}", n),
+            Tok::Ident(name) => f.write_str(name),
+            Tok::Op(op) => f.write_str(op),
+        }
+    }
+}
+
+const OPS: [&str; 17] = [
+    "**", "//", "<<", ">>", "+", "-", "*", "/", "%", "&", "|", "^", "~", "(", ")", ",", "=",
+];
+
+fn parse_literal(word: &str) -> Result<ix, CalcError> {
+    let digits: String = word.chars().filter(|&c| c != '_').collect();
+    let lower = digits.to_ascii_lowercase();
+    let (radix, body) = if let Some(rest) = lower.strip_prefix("0x") {
+        (16, rest)
+    } else if let Some(rest) = lower.strip_prefix("0b") {
+        (2, rest)
+    } else if let Some(rest) = lower.strip_prefix("0o") {
+        (8, rest)
+    } else {
+        (10, lower.as_str())
+    };
+    if body.starts_with(['+', '-']) {
+        return Err(CalcError::Syntax(format!("bad number {:?}", word)));
+    }
+    ix::from_str_radix(body, radix)
+        .map_err(|e| CalcError::Syntax(format!("bad number {:?}: {}", word, e)))
+}
+
+fn tokenize(line: &str) -> Result<Vec<Tok>, CalcError> {
+    let mut toks = vec![];
+    let mut rest = line;
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() || rest.starts_with('#') {
+            return Ok(toks);
+        }
+        let c = rest.chars().next().unwrap();
+        if c.is_ascii_alphanumeric() || c == '_' {
+            let end = rest
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                .unwrap_or(rest.len());
+            let word = &rest[..end];
+            toks.push(if c.is_ascii_digit() {
+                Tok::Num(parse_literal(word)?)
+            } else {
+                Tok::Ident(word.to_string())
+            });
+            rest = &rest[end..];
+        } else if let Some(op) = OPS.iter().find(|op| rest.starts_with(*op)) {
+            toks.push(Tok::Op(op));
+            rest = &rest[op.len()..];
+        } else {
+            return Err(CalcError::Syntax(format!("unexpected character {:?}", c)));
+        }
+    }
+}
+
+fn to_usize(x: &ix) -> Option<usize> {
+    u64::try_from(x).ok().and_then(|v| usize::try_from(v).ok())
+}
+
+fn small(x: &ix, what: &str) -> Result<usize, CalcError> {
+    to_usize(x).ok_or_else(|| CalcError::Domain(format!("{} is out of range", what)))
+}
+
+/// Largest result `**` and `<<` may build, so that a short line such as
+/// `10**10**10` fails at once instead of exhausting memory.
+const MAX_RESULT_BITS: usize = 1 << 24;
+
+fn too_large() -> CalcError {
+    CalcError::Domain(format!("result would exceed {} bits", MAX_RESULT_BITS))
+}
+
+fn abs(x: &ix) -> ix {
+    ix {
+        sign: false,
+        vals: x.vals.clone(),
+    }
+}
+
+fn truth(b: bool) -> ix {
+    ix::from(b as u64)
+}
+
+/// Evaluate one call to a built-in function.
+fn call(name: &str, args: &[ix]) -> Result<ix, CalcError> {
+    let arity = |n: usize| {
+        if args.len() == n {
+            Ok(())
+        } else {
+            Err(CalcError::BadCall(format!(
+                "{}() takes {} argument{}, got {}",
+                name,
+                n,
+                if n == 1 { "" } else { "s" },
+                args.len()
+            )))
+        }
+    };
+    let at_least_one = || {
+        if args.is_empty() {
+            Err(CalcError::BadCall(format!(
+                "{}() needs at least one argument",
+                name
+            )))
+        } else {
+            Ok(())
+        }
+    };
+    Ok(match name {
+        "abs" => {
+            arity(1)?;
+            abs(&args[0])
+        }
+        "gcd" => {
+            at_least_one()?;
+            args.iter().fold(zero_ix(), |g, x| g.gcd(x))
+        }
+        "lcm" => {
+            at_least_one()?;
+            args[1..].iter().fold(abs(&args[0]), |l, x| l.lcm(x))
+        }
+        "min" => {
+            at_least_one()?;
+            args.iter().min().unwrap().clone()
+        }
+        "max" => {
+            at_least_one()?;
+            args.iter().max().unwrap().clone()
+        }
+        "powmod" => {
+            arity(3)?;
+            let (base, exp, m) = (&args[0], &args[1], &args[2]);
+            if m.vals.is_empty() {
+                return Err(IxError::DivisionByZero.into());
+            }
+            if exp.sign && base.mod_inverse(m).is_none() {
+                return Err(CalcError::Domain(
+                    "powmod() with a negative exponent needs an invertible base".to_string(),
+                ));
+            }
+            base.pow_mod(exp, m)
+        }
+        "modinv" => {
+            arity(2)?;
+            if args[1].vals.is_empty() {
+                return Err(IxError::DivisionByZero.into());
+            }
+            args[0].mod_inverse(&args[1]).ok_or_else(|| {
+                CalcError::Domain("modinv() of a non-invertible value".to_string())
+            })?
+        }
+        "isqrt" => {
+            arity(1)?;
+            if args[0].sign {
+                return Err(CalcError::Domain(
+                    "isqrt() of a negative number".to_string(),
+                ));
+            }
+            args[0].isqrt()
+        }
+        "root" => {
+            arity(2)?;
+            let n = small(&args[1], "root degree")?;
+            let n = u32::try_from(n)
+                .ok()
+                .filter(|&n| n > 0)
+                .ok_or_else(|| CalcError::Domain("root degree must be positive".to_string()))?;
+            if args[0].sign && n % 2 == 0 {
+                return Err(CalcError::Domain(
+                    "even root of a negative number".to_string(),
+                ));
+            }
+            args[0].nth_root(n)
+        }
+        "isprime" => {
+            arity(1)?;
+            truth(args[0].is_probable_prime())
+        }
+        "nextprime" => {
+            arity(1)?;
+            args[0].next_prime()
+        }
+        "jacobi" => {
+            arity(2)?;
+            let n = &args[1];
+            if n.sign || n.vals.first().is_none_or(|v| v % 2 == 0) {
+                return Err(CalcError::Domain(
+                    "jacobi() needs an odd positive modulus".to_string(),
+                ));
+            }
+            ix::from(args[0].jacobi(n) as i64)
+        }
+        "bitlen" => {
+            arity(1)?;
+            ix::from(args[0].bit_len() as u64)
+        }
+        "popcount" => {
+            arity(1)?;
+            ix::from(args[0].count_ones())
+        }
+        _ => return Err(CalcError::BadCall(format!("unknown function {:?}", name))),
+    })
+}
+
+struct Parser<'a> {
+    toks: &'a [Tok],
+    pos: usize,
+    vars: &'a HashMap<String, ix>,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Tok> {
+        self.toks.get(self.pos)
+    }
+
+    /// Consume the next token if it is one of `ops`.
+    fn eat(&mut self, ops: &[&str]) -> Option<&'static str> {
+        match self.peek() {
+            Some(Tok::Op(op)) if ops.contains(op) => {
+                let op = *op;
+                self.pos += 1;
+                Some(op)
+            }
+            _ => None,
+        }
+    }
+
+    fn expect(&mut self, op: &str) -> Result<(), CalcError> {
+        self.eat(&[op])
+            .map(|_| ())
+            .ok_or_else(|| CalcError::Syntax(format!("expected `{}`", op)))
+    }
+
+    fn expr(&mut self) -> Result<ix, CalcError> {
+        let mut acc = self.xor()?;
+        while self.eat(&["|"]).is_some() {
+            acc = &acc | &self.xor()?;
+        }
+        Ok(acc)
+    }
+
+    fn xor(&mut self) -> Result<ix, CalcError> {
+        let mut acc = self.and()?;
+        while self.eat(&["^"]).is_some() {
+            acc = &acc ^ &self.and()?;
+        }
+        Ok(acc)
+    }
+
+    fn and(&mut self) -> Result<ix, CalcError> {
+        let mut acc = self.shift()?;
+        while self.eat(&["&"]).is_some() {
+            acc = &acc & &self.shift()?;
+        }
+        Ok(acc)
+    }
+
+    fn shift(&mut self) -> Result<ix, CalcError> {
+        let mut acc = self.sum()?;
+        while let Some(op) = self.eat(&["<<", ">>"]) {
+            let rhs = self.sum()?;
+            if rhs.sign {
+                return Err(CalcError::Domain("negative shift count".to_string()));
+            }
+            acc = if op == ">>" {
+                // Anything shifted past its top bit is 0 or -1.
+                let k = to_usize(&rhs).unwrap_or(usize::MAX).min(acc.bit_len() + 1);
+                &acc >> k
+            } else if acc.vals.is_empty() {
+                acc
+            } else {
+                let k = to_usize(&rhs)
+                    .filter(|k| acc.bit_len().saturating_add(*k) <= MAX_RESULT_BITS)
+                    .ok_or_else(too_large)?;
+                &acc << k
+            };
+        }
+        Ok(acc)
+    }
+
+    fn sum(&mut self) -> Result<ix, CalcError> {
+        let mut acc = self.term()?;
+        while let Some(op) = self.eat(&["+", "-"]) {
+            let rhs = self.term()?;
+            acc = if op == "+" { &acc + &rhs } else { &acc - &rhs };
+        }
+        Ok(acc)
+    }
+
+    fn term(&mut self) -> Result<ix, CalcError> {
+        let mut acc = self.unary()?;
+        while let Some(op) = self.eat(&["*", "/", "//", "%"]) {
+            let rhs = self.unary()?;
+            acc = match op {
+                "*" => &acc * &rhs,
+                "%" => acc.checked_rem(&rhs)?,
+                _ => acc.checked_div(&rhs)?,
+            };
+        }
+        Ok(acc)
+    }
+
+    fn unary(&mut self) -> Result<ix, CalcError> {
+        match self.eat(&["-", "+", "~"]) {
+            Some("-") => Ok(-self.unary()?),
+            Some("~") => Ok(!self.unary()?),
+            Some(_) => self.unary(),
+            None => self.power(),
+        }
+    }
+
+    fn power(&mut self) -> Result<ix, CalcError> {
+        let base = self.primary()?;
+        if self.eat(&["**"]).is_none() {
+            return Ok(base);
+        }
+        let exp = self.unary()?;
+        if exp.sign {
+            return Err(CalcError::Domain("negative exponent".to_string()));
+        }
+        // 0, 1 and -1 stay small under any exponent.
+        if base.vals.is_empty() || base.vals == [1] {
+            let odd = exp.vals.first().is_some_and(|v| v % 2 == 1);
+            return Ok(if exp.vals.is_empty() {
+                ix::from(1u64)
+            } else if base.sign && !odd {
+                -base
+            } else {
+                base
+            });
+        }
+        // |base| >= 2, so the result has more than (bit_len - 1) * exp bits.
+        let exp = to_usize(&exp)
+            .filter(|e| (base.bit_len() - 1).saturating_mul(*e) < MAX_RESULT_BITS)
+            .ok_or_else(too_large)?;
+        Ok(base.pow(exp as u32))
+    }
+
+    fn primary(&mut self) -> Result<ix, CalcError> {
+        let tok = self
+            .peek()
+            .cloned()
+            .ok_or_else(|| CalcError::Syntax("unexpected end of input".to_string()))?;
+        self.pos += 1;
+        match tok {
+            Tok::Num(n) => Ok(n),
+            Tok::Ident(name) => {
+                if self.eat(&["("]).is_none() {
+                    return self
+                        .vars
+                        .get(&name)
+                        .cloned()
+                        .ok_or(CalcError::UndefinedVariable(name));
+                }
+                let mut args = vec![];
+                if self.eat(&[")"]).is_none() {
+                    loop {
+                        args.push(self.expr()?);
+                        if self.eat(&[","]).is_none() {
+                            break;
+                        }
+                    }
+                    self.expect(")")?;
+                }
+                call(&name, &args)
+            }
+            Tok::Op("(") => {
+                let v = self.expr()?;
+                self.expect(")")?;
+                Ok(v)
+            }
+            Tok::Op(op) => Err(CalcError::Syntax(format!("unexpected `{}`", op))),
+        }
+    }
+}
+
+/// Line-oriented big-integer calculator with named variables. The result of
+/// the last expression statement is also available as `_`.
+#[derive(Clone, Debug, Default)]
+pub struct Calculator {
+    vars: HashMap<String, ix>,
+}
+
+impl Calculator {
+    pub fn new() -> Calculator {
+        Calculator::default()
+    }
+
+    /// Current value of a variable.
+    pub fn get(&self, name: &str) -> Option<&ix> {
+        self.vars.get(name)
+    }
+
+    /// Run one line. Expressions yield `Some(value)`; assignments, blank lines
+    /// and `#` comments yield `None`.
+    pub fn eval_line(&mut self, line: &str) -> Result<Option<ix>, CalcError> {
+        let toks = tokenize(line)?;
+        if toks.is_empty() {
+            return Ok(None);
+        }
+        let target = match &toks[..] {
+            [Tok::Ident(name), Tok::Op("="), ..] => Some(name.clone()),
+            _ => None,
+        };
+        let start = if target.is_some() { 2 } else { 0 };
+        let mut parser = Parser {
+            toks: &toks[start..],
+            pos: 0,
+            vars: &self.vars,
+        };
+        let value = parser.expr()?;
+        if let Some(tok) = parser.peek() {
+            return Err(CalcError::Syntax(format!("unexpected `{}`", tok)));
+        }
+        match target {
+            Some(name) => {
+                self.vars.insert(name, value);
+                Ok(None)
+            }
+            None => {
+                self.vars.insert("_".to_string(), value.clone());
+                Ok(Some(value))
+            }
+        }
+    }
+}
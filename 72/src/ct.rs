@@ -0,0 +1,253 @@
+use std::hint::black_box;
+use std::ops::{BitAnd, BitOr, Not};
+
+use crate::limb::{adc, mac, sbb};
+use crate::{Uint, ix, rem_ix};
+
+// Everything here runs in time that depends only on `LIMBS`, never on limb
+// values: no data-dependent branches, early exits or table indexing. Only
+// the moduli handed to `CtMontgomeryCtx::new` are treated as public.
+//
+// Masks pass through `black_box` where they are built and where they are
+// applied, as `subtle` does, so the optimizer cannot see that a `Choice` is
+// only ever 0 or 1 and turn a masked select back into a branch.
+
+/// A secret boolean held as an all-zeros or all-ones mask.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Choice(u64);
+
+impl Choice {
+    pub const FALSE: Choice = Choice(0);
+    pub const TRUE: Choice = Choice(u64::MAX);
+
+    pub fn from_bool(b: bool) -> Choice {
+        Choice::from_bit(b as u64)
+    }
+
+    /// `1` becomes true and `0` false; other values are not allowed.
+    fn from_bit(bit: u64) -> Choice {
+        Choice(0u64.wrapping_sub(black_box(bit)))
+    }
+
+    /// Reveal the value. Branching on the result is variable-time.
+    pub fn to_bool(self) -> bool {
+        self.0 != 0
+    }
+
+    pub fn mask(self) -> u64 {
+        black_box(self.0)
+    }
+}
+
+impl BitAnd for Choice {
+    type Output = Choice;
+    fn bitand(self, rhs: Choice) -> Choice {
+        Choice(self.0 & rhs.0)
+    }
+}
+
+impl BitOr for Choice {
+    type Output = Choice;
+    fn bitor(self, rhs: Choice) -> Choice {
+        Choice(self.0 | rhs.0)
+    }
+}
+
+impl Not for Choice {
+    type Output = Choice;
+    fn not(self) -> Choice {
+        Choice(!self.0)
+    }
+}
+
+/// True when `x` is zero.
+fn limb_is_zero(x: u64) -> Choice {
+    Choice::from_bit(((x | x.wrapping_neg()) >> 63) ^ 1)
+}
+
+impl<const LIMBS: usize> Uint<LIMBS> {
+    /// `b` if `choice` is true, else `a`.
+    pub fn ct_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut out = *a;
+        let mask = choice.mask();
+        for (o, &v) in out.limbs.iter_mut().zip(&b.limbs) {
+            *o ^= (*o ^ v) & mask;
+        }
+        out
+    }
+
+    /// Swap `a` and `b` if `choice` is true.
+    pub fn ct_swap(a: &mut Self, b: &mut Self, choice: Choice) {
+        let mask = choice.mask();
+        for (x, y) in a.limbs.iter_mut().zip(b.limbs.iter_mut()) {
+            let t = (*x ^ *y) & mask;
+            *x ^= t;
+            *y ^= t;
+        }
+    }
+
+    pub fn ct_is_zero(&self) -> Choice {
+        limb_is_zero(self.limbs.iter().fold(0, |acc, &v| acc | v))
+    }
+
+    pub fn ct_eq(&self, other: &Self) -> Choice {
+        let diff = self
+            .limbs
+            .iter()
+            .zip(&other.limbs)
+            .fold(0, |acc, (&x, &y)| acc | (x ^ y));
+        limb_is_zero(diff)
+    }
+
+    pub fn ct_lt(&self, other: &Self) -> Choice {
+        self.ct_sub(other).1
+    }
+
+    pub fn ct_gt(&self, other: &Self) -> Choice {
+        other.ct_sub(self).1
+    }
+
+    /// Wrapping sum and a mask that is true on carry out.
+    pub fn ct_add(&self, other: &Self) -> (Self, Choice) {
+        let mut out = Self::ZERO;
+        let mut carry = 0;
+        for i in 0..LIMBS {
+            (out.limbs[i], carry) = adc(self.limbs[i], other.limbs[i], carry);
+        }
+        (out, Choice::from_bit(carry))
+    }
+
+    /// Wrapping difference and a mask that is true on borrow out.
+    pub fn ct_sub(&self, other: &Self) -> (Self, Choice) {
+        let mut out = Self::ZERO;
+        let mut borrow = 0;
+        for i in 0..LIMBS {
+            (out.limbs[i], borrow) = sbb(self.limbs[i], other.limbs[i], borrow);
+        }
+        (out, Choice::from_bit(borrow))
+    }
+}
+
+/// Constant-time Montgomery arithmetic modulo a public odd `n`, with
+/// `R = 2^(64 * LIMBS)`. Unlike `MontgomeryCtx`, operands keep their full
+/// width and every step does the same work whatever the values.
+#[derive(Clone, Debug)]
+pub struct CtMontgomeryCtx<const LIMBS: usize> {
+    n: Uint<LIMBS>,
+    /// `-n^-1 mod 2^64`.
+    n0_inv: u64,
+    /// `R^2 mod n`.
+    r2: Uint<LIMBS>,
+    /// `R mod n`, the Montgomery form of one.
+    r1: Uint<LIMBS>,
+}
+
+impl<const LIMBS: usize> CtMontgomeryCtx<LIMBS> {
+    /// `None` unless `n` is odd and greater than one.
+    pub fn new(n: &Uint<LIMBS>) -> Option<Self> {
+        if n.limbs[0] & 1 == 0 || *n == Uint::ONE {
+            return None;
+        }
+        let mut inv: u64 = 1;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(n.limbs[0].wrapping_mul(inv)));
+        }
+        // The setup divides, which is fine because `n` is public.
+        let modulus = ix::from(n);
+        let reduce =
+            |bits: usize| Uint::try_from(rem_ix(&(ix::from(1u64) << bits), &modulus)).unwrap();
+        Some(CtMontgomeryCtx {
+            n: *n,
+            n0_inv: inv.wrapping_neg(),
+            r2: reduce(128 * LIMBS),
+            r1: reduce(64 * LIMBS),
+        })
+    }
+
+    pub fn modulus(&self) -> &Uint<LIMBS> {
+        &self.n
+    }
+
+    /// `a * b / R mod n` by coarsely integrated operand scanning. Needs
+    /// `a * b < n * R`, which holds whenever one operand is below `n`.
+    pub fn mul(&self, a: &Uint<LIMBS>, b: &Uint<LIMBS>) -> Uint<LIMBS> {
+        let n = &self.n.limbs;
+        let mut t = [0u64; LIMBS];
+        let mut t_hi = 0u64;
+        for &bi in &b.limbs {
+            let mut carry = 0;
+            for (tj, &aj) in t.iter_mut().zip(&a.limbs) {
+                (*tj, carry) = mac(*tj, aj, bi, carry);
+            }
+            let (hi, top) = adc(t_hi, carry, 0);
+
+            let m = t[0].wrapping_mul(self.n0_inv);
+            let (_, mut carry) = mac(t[0], m, n[0], 0);
+            for j in 1..LIMBS {
+                (t[j - 1], carry) = mac(t[j], m, n[j], carry);
+            }
+            let (low, c) = adc(hi, carry, 0);
+            t[LIMBS - 1] = low;
+            t_hi = top + c;
+        }
+        // t < 2n; subtract n once if t >= n, which includes any t_hi carry.
+        let t = Uint { limbs: t };
+        let (reduced, borrow) = t.ct_sub(&self.n);
+        let keep = borrow & !Choice::from_bit(t_hi);
+        Uint::ct_select(&reduced, &t, keep)
+    }
+
+    pub fn sqr(&self, a: &Uint<LIMBS>) -> Uint<LIMBS> {
+        self.mul(a, a)
+    }
+
+    /// Montgomery form of any `a` below `R`.
+    pub fn to_mont(&self, a: &Uint<LIMBS>) -> Uint<LIMBS> {
+        self.mul(a, &self.r2)
+    }
+
+    pub fn from_mont(&self, a: &Uint<LIMBS>) -> Uint<LIMBS> {
+        self.mul(a, &Uint::ONE)
+    }
+
+    /// `a + b mod n` for `a, b < n`.
+    pub fn add(&self, a: &Uint<LIMBS>, b: &Uint<LIMBS>) -> Uint<LIMBS> {
+        let (sum, carry) = a.ct_add(b);
+        let (reduced, borrow) = sum.ct_sub(&self.n);
+        Uint::ct_select(&reduced, &sum, borrow & !carry)
+    }
+
+    /// `a - b mod n` for `a, b < n`.
+    pub fn sub(&self, a: &Uint<LIMBS>, b: &Uint<LIMBS>) -> Uint<LIMBS> {
+        let (diff, borrow) = a.ct_sub(b);
+        let (wrapped, _) = diff.ct_add(&self.n);
+        Uint::ct_select(&diff, &wrapped, borrow)
+    }
+
+    /// `base^exp mod n` for any `base` below `R`, returned as an ordinary
+    /// residue. Uses a fixed 4-bit window over all `64 * LIMBS` exponent bits
+    /// and reads every table entry at each step.
+    pub fn pow(&self, base: &Uint<LIMBS>, exp: &Uint<LIMBS>) -> Uint<LIMBS> {
+        let mut table = [self.r1; 16];
+        table[1] = self.to_mont(base);
+        for i in 2..16 {
+            table[i] = self.mul(&table[i - 1], &table[1]);
+        }
+
+        let mut acc = self.r1;
+        for limb in exp.limbs.iter().rev() {
+            for shift in (0..16).rev() {
+                for _ in 0..4 {
+                    acc = self.sqr(&acc);
+                }
+                let window = (limb >> (shift * 4)) & 0xf;
+                let mut entry = table[0];
+                for (i, candidate) in table.iter().enumerate() {
+                    entry = Uint::ct_select(&entry, candidate, limb_is_zero(window ^ i as u64));
+                }
+                acc = self.mul(&acc, &entry);
+            }
+        }
+        self.from_mont(&acc)
+    }
+}
@@ -0,0 +1,363 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::radix::{ParseIxError, parse_point_digits};
+use crate::rounding::{RoundingMode, div_round};
+use crate::{IxError, div_rem_limb, ix, u64_to_ix, zero_ix};
+
+fn pow10(k: u32) -> ix {
+    u64_to_ix(10).pow(k)
+}
+
+/// Largest exponent magnitude a parsed `Decimal` may carry. `1e1000000`
+/// already has a mantissa of over three million bits.
+const MAX_EXPONENT: i64 = 1_000_000;
+
+/// The exponent in `s`, which starts at its `e` or `E`.
+fn parse_exponent(s: &str) -> Result<i64, ParseIxError> {
+    let body = &s[1..];
+    let digits = body.strip_prefix(['+', '-']).unwrap_or(body);
+    if let Some(c) = digits.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(match c {
+            '+' | '-' => ParseIxError::StraySign,
+            _ => ParseIxError::InvalidDigit(c),
+        });
+    }
+    if body.is_empty() {
+        return Err(ParseIxError::InvalidDigit(s.chars().next().unwrap()));
+    }
+    if digits.is_empty() {
+        return Err(ParseIxError::StraySign);
+    }
+    body.parse::<i64>()
+        .ok()
+        .filter(|e| e.unsigned_abs() <= MAX_EXPONENT as u64)
+        .ok_or(ParseIxError::ExponentOutOfRange)
+}
+
+/// Decimal number `mantissa * 10^-scale`, where the scale counts digits
+/// after the point. Addition, subtraction and multiplication are exact and
+/// widen the scale as needed; division and square roots round to a scale
+/// the caller picks.
+///
+/// Equality and ordering compare values, so `1.5 == 1.50`, but the scale is
+/// kept and shows in the formatted output.
+#[derive(Clone, Debug)]
+pub struct Decimal {
+    mantissa: ix,
+    scale: u32,
+}
+
+impl Decimal {
+    pub fn new(mantissa: ix, scale: u32) -> Decimal {
+        Decimal { mantissa, scale }
+    }
+
+    pub fn from_integer(n: ix) -> Decimal {
+        Decimal::new(n, 0)
+    }
+
+    pub fn zero() -> Decimal {
+        Decimal::from_integer(zero_ix())
+    }
+
+    pub fn mantissa(&self) -> &ix {
+        &self.mantissa
+    }
+
+    pub fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.mantissa.vals.is_empty()
+    }
+
+    pub fn abs(&self) -> Decimal {
+        Decimal::new(
+            ix {
+                sign: false,
+                vals: self.mantissa.vals.clone(),
+            },
+            self.scale,
+        )
+    }
+
+    /// The mantissa of `self` at a scale at least as large as its own.
+    fn widened(&self, scale: u32) -> ix {
+        &self.mantissa * pow10(scale - self.scale)
+    }
+
+    /// The same value with `scale` digits after the point, rounded by
+    /// `mode` if that drops digits.
+    pub fn with_scale(&self, scale: u32, mode: RoundingMode) -> Decimal {
+        if scale >= self.scale {
+            return Decimal::new(self.widened(scale), scale);
+        }
+        let m = div_round(&self.mantissa, &pow10(self.scale - scale), mode);
+        Decimal::new(m, scale)
+    }
+
+    /// The same value with trailing zeros after the point removed.
+    pub fn normalized(&self) -> Decimal {
+        let mut out = self.clone();
+        while out.scale > 0 {
+            let (q, r) = div_rem_limb(&out.mantissa.vals, 10);
+            if r != 0 || out.is_zero() {
+                break;
+            }
+            out.mantissa.vals = q;
+            out.scale -= 1;
+        }
+        if out.is_zero() {
+            out.scale = 0;
+        }
+        out
+    }
+
+    /// `self / rhs` with `scale` digits after the point, rounded by `mode`,
+    /// or `Err(DivisionByZero)`.
+    pub fn checked_div(
+        &self,
+        rhs: &Decimal,
+        scale: u32,
+        mode: RoundingMode,
+    ) -> Result<Decimal, IxError> {
+        if rhs.is_zero() {
+            return Err(IxError::DivisionByZero);
+        }
+        // (a / 10^s1) / (b / 10^s2) * 10^scale = a * 10^(scale + s2 - s1) / b.
+        let shift = scale as i64 + rhs.scale as i64 - self.scale as i64;
+        let (mut n, mut d) = if shift >= 0 {
+            (&self.mantissa * pow10(shift as u32), rhs.mantissa.clone())
+        } else {
+            (
+                self.mantissa.clone(),
+                &rhs.mantissa * pow10(shift.unsigned_abs() as u32),
+            )
+        };
+        if d.sign {
+            n = -n;
+            d = -d;
+        }
+        Ok(Decimal::new(div_round(&n, &d, mode), scale))
+    }
+
+    /// As `checked_div`, but panics on division by zero.
+    pub fn div(&self, rhs: &Decimal, scale: u32, mode: RoundingMode) -> Decimal {
+        self.checked_div(rhs, scale, mode)
+            .unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Square root with `scale` digits after the point, rounded by `mode`.
+    /// Panics if `self` is negative.
+    pub fn sqrt(&self, scale: u32, mode: RoundingMode) -> Decimal {
+        assert!(!self.mantissa.sign, "square root of a negative number");
+        // The root of n / d scaled by 10^scale, where n / d = self * 10^(2 * scale).
+        let t = 2 * scale as i64 - self.scale as i64;
+        let (n, d) = if t >= 0 {
+            (&self.mantissa * pow10(t as u32), u64_to_ix(1))
+        } else {
+            (self.mantissa.clone(), pow10(t.unsigned_abs() as u32))
+        };
+        // floor(sqrt(x)) == isqrt(floor(x)).
+        let r = (&n / &d).isqrt();
+        let exact = &r * &r * &d == n;
+        // Compare sqrt(n / d) with r + 1/2, that is 4n with (2r + 1)^2 d.
+        let odd = &r + &r + u64_to_ix(1);
+        let half = (u64_to_ix(4) * &n).cmp(&(&odd * &odd * &d));
+        let up = match mode {
+            RoundingMode::Floor | RoundingMode::Down => false,
+            RoundingMode::Ceil | RoundingMode::Up => !exact,
+            RoundingMode::Nearest => match half {
+                Ordering::Less => false,
+                Ordering::Greater => true,
+                Ordering::Equal => r.vals.first().is_some_and(|v| v & 1 == 1),
+            },
+            RoundingMode::HalfUp => half != Ordering::Less,
+        };
+        let r = if up { r + u64_to_ix(1) } else { r };
+        Decimal::new(r, scale)
+    }
+
+    /// π to `digits` places after the point, rounded to nearest, from
+    /// Machin's formula `π = 16 atan(1/5) - 4 atan(1/239)`. Ten guard digits
+    /// absorb the truncation in each series term, so the last digit can
+    /// only be off when the digits after it are a long run of 9s or 0s.
+    pub fn pi(digits: u32) -> Decimal {
+        let scale = digits + 10;
+        let unit = pow10(scale);
+        let pi = u64_to_ix(16) * atan_inv(5, &unit) - u64_to_ix(4) * atan_inv(239, &unit);
+        Decimal::new(pi, scale).with_scale(digits, RoundingMode::Nearest)
+    }
+}
+
+/// `atan(1/x) * unit` by its Taylor series, each term truncated.
+fn atan_inv(x: u64, unit: &ix) -> ix {
+    let x2 = u64_to_ix(x * x);
+    let mut power = unit / &u64_to_ix(x);
+    let mut sum = power.clone();
+    let mut k = 1u64;
+    while !power.vals.is_empty() {
+        power = &power / &x2;
+        let term = &power / &u64_to_ix(2 * k + 1);
+        sum = if k % 2 == 1 { sum - term } else { sum + term };
+        k += 1;
+    }
+    sum
+}
+
+impl Default for Decimal {
+    fn default() -> Decimal {
+        Decimal::zero()
+    }
+}
+
+impl From<ix> for Decimal {
+    fn from(n: ix) -> Decimal {
+        Decimal::from_integer(n)
+    }
+}
+
+impl From<i64> for Decimal {
+    fn from(n: i64) -> Decimal {
+        Decimal::from_integer(ix::from(n))
+    }
+}
+
+impl Ord for Decimal {
+    fn cmp(&self, other: &Decimal) -> Ordering {
+        let scale = self.scale.max(other.scale);
+        self.widened(scale).cmp(&other.widened(scale))
+    }
+}
+
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Decimal) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Decimal {
+    fn eq(&self, other: &Decimal) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Decimal {}
+
+fn add_decimal(a: &Decimal, b: &Decimal) -> Decimal {
+    let scale = a.scale.max(b.scale);
+    Decimal::new(a.widened(scale) + b.widened(scale), scale)
+}
+
+fn sub_decimal(a: &Decimal, b: &Decimal) -> Decimal {
+    let scale = a.scale.max(b.scale);
+    Decimal::new(a.widened(scale) - b.widened(scale), scale)
+}
+
+fn mul_decimal(a: &Decimal, b: &Decimal) -> Decimal {
+    Decimal::new(&a.mantissa * &b.mantissa, a.scale + b.scale)
+}
+
+macro_rules! decimal_binop {
+    ($Trait:ident, $method:ident, $AssignTrait:ident, $assign:ident, $func:ident) => {
+        impl $Trait<&Decimal> for &Decimal {
+            type Output = Decimal;
+            fn $method(self, rhs: &Decimal) -> Decimal {
+                $func(self, rhs)
+            }
+        }
+
+        impl $Trait<Decimal> for &Decimal {
+            type Output = Decimal;
+            fn $method(self, rhs: Decimal) -> Decimal {
+                $func(self, &rhs)
+            }
+        }
+
+        impl $Trait<&Decimal> for Decimal {
+            type Output = Decimal;
+            fn $method(self, rhs: &Decimal) -> Decimal {
+                $func(&self, rhs)
+            }
+        }
+
+        impl $Trait<Decimal> for Decimal {
+            type Output = Decimal;
+            fn $method(self, rhs: Decimal) -> Decimal {
+                $func(&self, &rhs)
+            }
+        }
+
+        impl $AssignTrait<&Decimal> for Decimal {
+            fn $assign(&mut self, rhs: &Decimal) {
+                *self = $func(self, rhs);
+            }
+        }
+
+        impl $AssignTrait<Decimal> for Decimal {
+            fn $assign(&mut self, rhs: Decimal) {
+                *self = $func(self, &rhs);
+            }
+        }
+    };
+}
+
+decimal_binop!(Add, add, AddAssign, add_assign, add_decimal);
+decimal_binop!(Sub, sub, SubAssign, sub_assign, sub_decimal);
+decimal_binop!(Mul, mul, MulAssign, mul_assign, mul_decimal);
+
+impl Neg for Decimal {
+    type Output = Decimal;
+    fn neg(self) -> Decimal {
+        Decimal::new(-self.mantissa, self.scale)
+    }
+}
+
+impl Neg for &Decimal {
+    type Output = Decimal;
+    fn neg(self) -> Decimal {
+        -self.clone()
+    }
+}
+
+/// All `scale` digits after the point are written, so `1.50` stays `1.50`.
+impl fmt::Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let digits = self.abs().mantissa.to_string();
+        let scale = self.scale as usize;
+        let body = if scale == 0 {
+            digits
+        } else {
+            let padded = format!("{:0>width$}", digits, width = scale + 1);
+            let (whole, frac) = padded.split_at(padded.len() - scale);
+            format!("{}.{}", whole, frac)
+        };
+        f.pad_integral(!self.mantissa.sign, "", &body)
+    }
+}
+
+/// Parse an optionally signed decimal such as `"-12.3400"`, `".5"` or
+/// `"6.02e23"`. The scale is the number of digits after the point, less
+/// any exponent, and never below zero.
+impl FromStr for Decimal {
+    type Err = ParseIxError;
+
+    fn from_str(s: &str) -> Result<Decimal, ParseIxError> {
+        let (number, exp) = match s.find(['e', 'E']) {
+            Some(i) => (&s[..i], parse_exponent(&s[i..])?),
+            None => (s, 0),
+        };
+        let (mantissa, frac_digits) = parse_point_digits(number)?;
+        let exp = exp - frac_digits as i64;
+        if exp <= 0 {
+            let scale = u32::try_from(-exp).map_err(|_| ParseIxError::ExponentOutOfRange)?;
+            Ok(Decimal::new(mantissa, scale))
+        } else {
+            Ok(Decimal::new(mantissa * pow10(exp as u32), 0))
+        }
+    }
+}
@@ -1,8 +1,88 @@
 #![allow(non_camel_case_types)]
 
+mod barrett;
+mod bits;
+mod bytes;
+mod calc;
+mod ct;
+mod decimal;
+mod limb;
+mod modular;
+mod mont;
+mod mul;
+mod numtheory;
+mod ops;
+mod pem;
+mod prime;
+mod radix;
+mod ratio;
+mod rounding;
+mod rsa;
+#[cfg(feature = "serde")]
+mod serde_impl;
+mod sha512;
+mod uint;
+mod varint;
+
+use limb::{add_assign_limbs, sub_assign_limbs};
+
+pub use barrett::BarrettCtx;
+pub use bits::{and_ix, not_ix, or_ix, shl_ix, shr_ix, xor_ix};
+pub use bytes::BytesOverflowError;
+pub use calc::{CalcError, Calculator};
+pub use ct::{Choice, CtMontgomeryCtx};
+pub use decimal::Decimal;
+pub use mont::MontgomeryCtx;
+pub use mul::{KARATSUBA_THRESHOLD, TOOM3_THRESHOLD, mul_mag, mul_schoolbook, sqr_mag, sqr_schoolbook};
+pub use ops::TryFromIxError;
+pub use prime::{RandomSource, is_prime_u64, random_below, random_bits, random_prime};
+pub use radix::ParseIxError;
+pub use ratio::Ratio;
+pub use rounding::RoundingMode;
+pub use rsa::{RsaError, RsaPrivateKey, RsaPublicKey};
+pub use sha512::sha512;
+pub use uint::{U256, U512, Uint};
+pub use varint::VarintError;
+
+#[cfg(feature = "serde")]
+pub use serde_impl::hex as serde_hex;
+
+/// Error returned by the fallible `checked_*` and `try_*` functions.
 #[derive(Clone, Debug, PartialEq, Eq)]
+pub enum IxError {
+    /// The divisor or modulus was zero.
+    DivisionByZero,
+    /// The input string was not a valid number.
+    Parse(ParseIxError),
+}
+
+impl std::fmt::Display for IxError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            IxError::DivisionByZero => f.write_str("division by zero"),
+            IxError::Parse(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for IxError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            IxError::DivisionByZero => None,
+            IxError::Parse(e) => Some(e),
+        }
+    }
+}
+
+impl From<ParseIxError> for IxError {
+    fn from(e: ParseIxError) -> IxError {
+        IxError::Parse(e)
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
 pub struct ix {
-    pub sign: bool,     
+    pub sign: bool,
     pub vals: Vec<u64>,
 }
 
@@ -34,7 +114,7 @@ pub fn u64_to_ix(val: u64) -> ix {
     }
 }
 
-pub fn gte_mag(a_vals: &Vec<u64>, b_vals: &Vec<u64>) -> bool {
+pub fn gte_mag(a_vals: &[u64], b_vals: &[u64]) -> bool {
     if a_vals.len() != b_vals.len() {
         return a_vals.len() > b_vals.len();
     }
@@ -46,7 +126,7 @@ pub fn gte_mag(a_vals: &Vec<u64>, b_vals: &Vec<u64>) -> bool {
     true
 }
 
-pub fn cmp_mag(a_vals: &Vec<u64>, b_vals: &Vec<u64>) -> i8 {
+pub fn cmp_mag(a_vals: &[u64], b_vals: &[u64]) -> i8 {
     if a_vals.len() != b_vals.len() {
         return if a_vals.len() < b_vals.len() { -1 } else { 1 };
     }
@@ -61,39 +141,25 @@ pub fn cmp_mag(a_vals: &Vec<u64>, b_vals: &Vec<u64>) -> i8 {
     0
 }
 
-pub fn add_mag(aug_vals: &Vec<u64>, add_vals: &Vec<u64>) -> Vec<u64> {
-    let mut out = Vec::with_capacity(std::cmp::max(aug_vals.len(), add_vals.len()) + 1);
-    let mut carry: u128 = 0;
-    let n = std::cmp::max(aug_vals.len(), add_vals.len());
-    for i in 0..n {
-        let a = if i < aug_vals.len() { aug_vals[i] as u128 } else { 0 };
-        let b = if i < add_vals.len() { add_vals[i] as u128 } else { 0 };
-        let sum = a + b + carry;
-        out.push((sum & ((1u128 << 64) - 1)) as u64);
-        carry = sum >> 64;
-    }
+pub fn add_mag(aug_vals: &[u64], add_vals: &[u64]) -> Vec<u64> {
+    let (long, short) = if aug_vals.len() >= add_vals.len() {
+        (aug_vals, add_vals)
+    } else {
+        (add_vals, aug_vals)
+    };
+    let mut out = Vec::with_capacity(long.len() + 1);
+    out.extend_from_slice(long);
+    let carry = add_assign_limbs(&mut out, short);
     if carry != 0 {
-        out.push(carry as u64);
+        out.push(carry);
     }
     normalize_vals(&mut out);
     out
 }
 
-pub fn sub_mag(min_vals: &Vec<u64>, sub_vals: &Vec<u64>) -> Vec<u64> {
-    let mut out = Vec::with_capacity(min_vals.len());
-    let mut borrow: i128 = 0;
-    for i in 0..min_vals.len() {
-        let a = min_vals[i] as i128;
-        let b = if i < sub_vals.len() { sub_vals[i] as i128 } else { 0 };
-        let mut cur = a - b - borrow;
-        if cur < 0 {
-            cur += 1i128 << 64;
-            borrow = 1;
-        } else {
-            borrow = 0;
-        }
-        out.push(cur as u64);
-    }
+pub fn sub_mag(min_vals: &[u64], sub_vals: &[u64]) -> Vec<u64> {
+    let mut out = min_vals.to_vec();
+    sub_assign_limbs(&mut out, sub_vals);
     normalize_vals(&mut out);
     out
 }
@@ -137,7 +203,7 @@ pub fn add_ix(a: &ix, b: &ix) -> ix {
 
 pub fn sub_ix(a: &ix, b: &ix) -> ix {
     let b = ix {
-        sign: !b.sign,
+        sign: !b.sign && !b.vals.is_empty(),
         vals: b.vals.clone(),
     };
     add_ix(a, &b)
@@ -147,32 +213,11 @@ pub fn mul_ix(a: &ix, b: &ix) -> ix {
     if a.vals.is_empty() || b.vals.is_empty() {
         return zero_ix();
     }
-    let n = a.vals.len();
-    let m = b.vals.len();
-    let mut out = vec![0u64; n + m];
-    for i in 0..n {
-        let mut carry: u128 = 0;
-        for j in 0..m {
-            let idx = i + j;
-            let prod = (a.vals[i] as u128) * (b.vals[j] as u128) + (out[idx] as u128) + carry;
-            out[idx] = (prod & ((1u128 << 64) - 1)) as u64;
-            carry = prod >> 64;
-        }
-        if carry != 0 {
-            let mut k = i + m;
-            let mut c = carry as u128;
-            while c != 0 {
-                if k >= out.len() {
-                    out.push(0);
-                }
-                let sum = (out[k] as u128) + c;
-                out[k] = (sum & ((1u128 << 64) - 1)) as u64;
-                c = sum >> 64;
-                k += 1;
-            }
-        }
-    }
-    normalize_vals(&mut out);
+    let out = if a.vals == b.vals {
+        sqr_mag(&a.vals)
+    } else {
+        mul_mag(&a.vals, &b.vals)
+    };
     let sign = a.sign ^ b.sign;
     let mut res = ix { sign, vals: out };
     if res.vals.is_empty() {
@@ -181,7 +226,15 @@ pub fn mul_ix(a: &ix, b: &ix) -> ix {
     res
 }
 
-pub fn bit_len(vals: &Vec<u64>) -> usize {
+/// Square of `a`; the same as `mul_ix(a, a)`.
+pub fn sqr_ix(a: &ix) -> ix {
+    ix {
+        sign: false,
+        vals: sqr_mag(&a.vals),
+    }
+}
+
+pub fn bit_len(vals: &[u64]) -> usize {
     if vals.is_empty() {
         return 0;
     }
@@ -190,7 +243,7 @@ pub fn bit_len(vals: &Vec<u64>) -> usize {
     (vals.len() - 1) * 64 + top_bits
 }
 
-pub fn shl_mag(vals: &Vec<u64>, k: usize) -> Vec<u64> {
+pub fn shl_mag(vals: &[u64], k: usize) -> Vec<u64> {
     if vals.is_empty() {
         return vec![];
     }
@@ -198,9 +251,7 @@ pub fn shl_mag(vals: &Vec<u64>, k: usize) -> Vec<u64> {
     let rem = k % 64;
     let mut out = vec![0u64; limb_shift + vals.len() + 1];
     if rem == 0 {
-        for i in 0..vals.len() {
-            out[i + limb_shift] = vals[i];
-        }
+        out[limb_shift..limb_shift + vals.len()].copy_from_slice(vals);
     } else {
         for i in 0..vals.len() {
             let low = vals[i].wrapping_shl(rem as u32);
@@ -213,13 +264,30 @@ pub fn shl_mag(vals: &Vec<u64>, k: usize) -> Vec<u64> {
     out
 }
 
+pub fn shr_mag(vals: &[u64], k: usize) -> Vec<u64> {
+    let limb_shift = k / 64;
+    if limb_shift >= vals.len() {
+        return vec![];
+    }
+    let rem = k % 64;
+    let mut out = vals[limb_shift..].to_vec();
+    if rem != 0 {
+        for i in 0..out.len() {
+            let high = out.get(i + 1).map_or(0, |v| v << (64 - rem));
+            out[i] = (out[i] >> rem) | high;
+        }
+    }
+    normalize_vals(&mut out);
+    out
+}
+
 pub fn add_bit_to_vec(vals: &mut Vec<u64>, k: usize) {
     let limb = k / 64;
     let bit = k % 64;
     if vals.len() <= limb {
         vals.resize(limb + 1, 0);
     }
-    let mut carry: u128 = (1u128 << bit) as u128;
+    let mut carry: u128 = 1u128 << bit;
     let mut i = limb;
     while carry != 0 {
         if i >= vals.len() {
@@ -232,120 +300,367 @@ pub fn add_bit_to_vec(vals: &mut Vec<u64>, k: usize) {
     }
 }
 
-pub fn div_rem_mag(a_vals: &Vec<u64>, b_vals: &Vec<u64>) -> (Vec<u64>, Vec<u64>) {
+/// Schoolbook long division (Knuth, TAOCP vol. 2, 4.3.1, Algorithm D):
+/// one quotient limb per step, estimated from the top two limbs of the
+/// normalized remainder and corrected at most twice.
+pub fn div_rem_mag(a_vals: &[u64], b_vals: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    checked_div_rem_mag(a_vals, b_vals).unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// `div_rem_mag`, or `Err(DivisionByZero)` for an empty divisor.
+pub fn checked_div_rem_mag(
+    a_vals: &[u64],
+    b_vals: &[u64],
+) -> Result<(Vec<u64>, Vec<u64>), IxError> {
     if b_vals.is_empty() {
-        panic!("division by zero");
+        return Err(IxError::DivisionByZero);
     }
     if a_vals.is_empty() {
-        return (vec![], vec![]);
+        return Ok((vec![], vec![]));
     }
     if cmp_mag(a_vals, b_vals) < 0 {
-        return (vec![], a_vals.clone());
-    }
-
-    let mut rem = a_vals.clone();
-    let mut q: Vec<u64> = vec![];
-
-    let a_bits = bit_len(a_vals);
-    let b_bits = bit_len(b_vals);
-    let mut shift = a_bits as isize - b_bits as isize;
+        return Ok((vec![], a_vals.to_vec()));
+    }
+    if b_vals.len() == 1 {
+        let (q, r) = div_rem_limb(a_vals, b_vals[0]);
+        let rem = if r == 0 { vec![] } else { vec![r] };
+        return Ok((q, rem));
+    }
+
+    // D1: shift so the divisor's top limb has its high bit set.
+    let shift = b_vals[b_vals.len() - 1].leading_zeros();
+    let v = shl_bits(b_vals, shift, 0);
+    let mut u = shl_bits(a_vals, shift, 1);
+    let n = v.len();
+    let m = a_vals.len() - n;
+    let mut q = vec![0u64; m + 1];
+
+    for j in (0..=m).rev() {
+        // D3: estimate qhat from the top two limbs, refine with the third.
+        let num = ((u[j + n] as u128) << 64) | u[j + n - 1] as u128;
+        let mut qhat = num / v[n - 1] as u128;
+        let mut rhat = num % v[n - 1] as u128;
+        while qhat >> 64 != 0 || qhat * v[n - 2] as u128 > ((rhat << 64) | u[j + n - 2] as u128) {
+            qhat -= 1;
+            rhat += v[n - 1] as u128;
+            if rhat >> 64 != 0 {
+                break;
+            }
+        }
 
-    while shift >= 0 {
-        let shift_usize = shift as usize;
-        let t = shl_mag(b_vals, shift_usize);
-        if cmp_mag(&t, &rem) <= 0 {
-            rem = sub_mag(&rem, &t);
-            add_bit_to_vec(&mut q, shift_usize);
+        // D4: u[j..=j+n] -= qhat * v.
+        let mut carry: u64 = 0;
+        let mut borrow: u64 = 0;
+        for i in 0..n {
+            let p = qhat * v[i] as u128 + carry as u128;
+            carry = (p >> 64) as u64;
+            let (t, b1) = u[i + j].overflowing_sub(p as u64);
+            let (t, b2) = t.overflowing_sub(borrow);
+            u[i + j] = t;
+            borrow = b1 as u64 + b2 as u64;
         }
-        shift -= 1;
+        let (t, b1) = u[j + n].overflowing_sub(carry);
+        let (t, b2) = t.overflowing_sub(borrow);
+        u[j + n] = t;
+
+        // D6: qhat was one too large; add the divisor back.
+        if b1 || b2 {
+            qhat -= 1;
+            let mut c: u128 = 0;
+            for i in 0..n {
+                let sum = u[i + j] as u128 + v[i] as u128 + c;
+                u[i + j] = sum as u64;
+                c = sum >> 64;
+            }
+            u[j + n] = u[j + n].wrapping_add(c as u64);
+        }
+        q[j] = qhat as u64;
+    }
+
+    // D8: the remainder is the low n limbs, shifted back down.
+    let mut rem = vec![0u64; n];
+    for i in 0..n {
+        rem[i] = if shift == 0 {
+            u[i]
+        } else {
+            (u[i] >> shift) | (u[i + 1] << (64 - shift))
+        };
     }
     normalize_vals(&mut q);
     normalize_vals(&mut rem);
-    (q, rem)
+    Ok((q, rem))
 }
 
-pub fn div_ix(a: &ix, b: &ix) -> ix {
-    if b.vals.is_empty() {
+/// Shift left by `s < 64` bits into a vector with `extra` spare top limbs.
+fn shl_bits(vals: &[u64], s: u32, extra: usize) -> Vec<u64> {
+    let mut out = vec![0u64; vals.len() + extra];
+    if s == 0 {
+        out[..vals.len()].copy_from_slice(vals);
+        return out;
+    }
+    let mut carry = 0u64;
+    for i in 0..vals.len() {
+        out[i] = (vals[i] << s) | carry;
+        carry = vals[i] >> (64 - s);
+    }
+    if extra > 0 {
+        out[vals.len()] = carry;
+    }
+    out
+}
+
+/// Divide a magnitude by a single limb, returning the quotient and the remainder.
+pub fn div_rem_limb(vals: &[u64], d: u64) -> (Vec<u64>, u64) {
+    if d == 0 {
         panic!("division by zero");
     }
-    if a.vals.is_empty() {
-        return zero_ix();
+    let mut q = vec![0u64; vals.len()];
+    let mut rem: u128 = 0;
+    for i in (0..vals.len()).rev() {
+        let cur = (rem << 64) | vals[i] as u128;
+        q[i] = (cur / d as u128) as u64;
+        rem = cur % d as u128;
     }
+    normalize_vals(&mut q);
+    (q, rem as u64)
+}
 
-    let (mut q_vals, r_vals) = div_rem_mag(&a.vals, &b.vals);
+/// Below this many limbs `reciprocal_mag` uses one long division.
+const NEWTON_THRESHOLD: usize = 32;
+
+/// `floor(2^(128·k) / n)` for a `k`-limb magnitude `n`, by one Newton step
+/// from the reciprocal of the top half of `n`. The cost is a few
+/// multiplications of `k`-limb numbers instead of a quadratic division.
+pub(crate) fn reciprocal_mag(n: &[u64]) -> Vec<u64> {
+    let k = n.len();
+    if k < NEWTON_THRESHOLD {
+        let mut b2k = vec![0u64; 2 * k + 1];
+        b2k[2 * k] = 1;
+        return div_rem_mag(&b2k, n).0;
+    }
+    // With h > k/2 + 1 limbs of n, y·2^(64·m) is within a relative error
+    // of about 2^(-64·(h-1)) of the answer, and the Newton step squares
+    // that, leaving an error of a few units.
+    let h = k / 2 + 2;
+    let m = k - h;
+    let y = ix {
+        sign: false,
+        vals: reciprocal_mag(&n[m..]),
+    };
+    let n = ix {
+        sign: false,
+        vals: n.to_vec(),
+    };
+    // x = x0 + x0·e / 2^(128·k) with x0 = y·2^(64·m) and e = 2^(128·k) - n·x0.
+    // Only the top limbs of e affect the result: dropping k - 2 of them
+    // changes the correction by less than one.
+    let b2k = shl_ix(&u64_to_ix(1), 128 * k);
+    let e = sub_ix(&shr_ix(&b2k, 64 * m), &mul_ix(&n, &y));
+    let e = shr_ix(&e, 64 * (k - 2 - m));
+    let correction = shr_ix(&mul_ix(&y, &e), 64 * (h + 2));
+    let mut x = add_ix(&shl_ix(&y, 64 * m), &correction);
+    let mut r = sub_ix(&b2k, &mul_ix(&n, &x));
+    let one = u64_to_ix(1);
+    while r.sign {
+        x = sub_ix(&x, &one);
+        r = add_ix(&r, &n);
+    }
+    while gte_mag(&r.vals, &n.vals) {
+        x = add_ix(&x, &one);
+        r = sub_ix(&r, &n);
+    }
+    x.vals
+}
 
-    if (a.sign ^ b.sign) && !r_vals.is_empty() {
-        q_vals = add_mag(&q_vals, &vec![1u64]);
+/// Quotient and remainder of `x` by a `k`-limb `n`, given
+/// `mu = reciprocal_mag(n)` and `x < 2^(128·k)`. The quotient estimate
+/// from the top limbs of `x` is at most two short (HAC 14.42).
+pub(crate) fn div_rem_reciprocal(x: &[u64], n: &[u64], mu: &[u64]) -> (Vec<u64>, Vec<u64>) {
+    let k = n.len();
+    debug_assert!(x.len() <= 2 * k);
+    let q1 = x.get(k - 1..).unwrap_or(&[]);
+    let q2 = mul_mag(q1, mu);
+    let mut q = if q2.len() > k + 1 {
+        q2[k + 1..].to_vec()
+    } else {
+        vec![]
+    };
+    let mut r = sub_mag(x, &mul_mag(&q, n));
+    while gte_mag(&r, n) {
+        r = sub_mag(&r, n);
+        q = add_mag(&q, &[1]);
     }
+    (q, r)
+}
 
-    let mut q = ix {
-        sign: a.sign ^ b.sign,
+/// Floor quotient and remainder in one division: the quotient rounds towards
+/// negative infinity and the remainder takes the sign of `b`.
+pub fn checked_div_rem_ix(a: &ix, b: &ix) -> Result<(ix, ix), IxError> {
+    let (mut q_vals, mut r_vals) = checked_div_rem_mag(&a.vals, &b.vals)?;
+    if (a.sign ^ b.sign) && !r_vals.is_empty() {
+        q_vals = add_mag(&q_vals, &[1u64]);
+        r_vals = sub_mag(&b.vals, &r_vals);
+    }
+    normalize_vals(&mut q_vals);
+    normalize_vals(&mut r_vals);
+    let q = ix {
+        sign: (a.sign ^ b.sign) && !q_vals.is_empty(),
         vals: q_vals,
     };
-    normalize_vals(&mut q.vals);
-    if q.vals.is_empty() {
-        q.sign = false;
-    }
-    q
+    let r = ix {
+        sign: b.sign && !r_vals.is_empty(),
+        vals: r_vals,
+    };
+    Ok((q, r))
+}
+
+pub fn div_ix(a: &ix, b: &ix) -> ix {
+    a.checked_div(b).unwrap_or_else(|e| panic!("{}", e))
 }
 
 pub fn rem_ix(a: &ix, b: &ix) -> ix {
-    if b.vals.is_empty() {
-        panic!("division by zero");
+    a.checked_rem(b).unwrap_or_else(|e| panic!("{}", e))
+}
+
+impl ix {
+    /// Parse decimal like `str::parse`, with the error as an `IxError`.
+    pub fn try_parse(s: &str) -> Result<ix, IxError> {
+        Ok(s.parse()?)
     }
-    if a.vals.is_empty() {
-        return zero_ix();
+
+    /// `div_ix`, or `Err(DivisionByZero)` instead of a panic.
+    pub fn checked_div(&self, other: &ix) -> Result<ix, IxError> {
+        checked_div_rem_ix(self, other).map(|(q, _)| q)
     }
 
-    let (_q_vals, mut r_vals) = div_rem_mag(&a.vals, &b.vals);
-    if (a.sign ^ b.sign) && !r_vals.is_empty() {
-        r_vals = sub_mag(&b.vals, &r_vals);
+    /// `rem_ix`, or `Err(DivisionByZero)` instead of a panic.
+    pub fn checked_rem(&self, other: &ix) -> Result<ix, IxError> {
+        checked_div_rem_ix(self, other).map(|(_, r)| r)
     }
 
-    let mut r = ix {
-        sign: b.sign && !r_vals.is_empty(),
-        vals: r_vals,
-    };
-    normalize_vals(&mut r.vals);
-    if r.vals.is_empty() {
-        r.sign = false;
+    /// Both `div_ix` and `rem_ix` from a single division.
+    pub fn checked_div_rem(&self, other: &ix) -> Result<(ix, ix), IxError> {
+        checked_div_rem_ix(self, other)
+    }
+
+    /// Quotient rounded towards zero and a remainder with the sign of
+    /// `self`, like `/` and `%` on Rust's primitive integers.
+    pub fn checked_div_rem_trunc(&self, other: &ix) -> Result<(ix, ix), IxError> {
+        let (q_vals, r_vals) = checked_div_rem_mag(&self.vals, &other.vals)?;
+        let q = ix {
+            sign: (self.sign ^ other.sign) && !q_vals.is_empty(),
+            vals: q_vals,
+        };
+        let r = ix {
+            sign: self.sign && !r_vals.is_empty(),
+            vals: r_vals,
+        };
+        Ok((q, r))
+    }
+
+    /// Euclidean division: the remainder is always in `0..|other|`.
+    pub fn checked_div_rem_euclid(&self, other: &ix) -> Result<(ix, ix), IxError> {
+        let (q, r) = self.checked_div_rem_trunc(other)?;
+        if !r.sign {
+            return Ok((q, r));
+        }
+        let one = u64_to_ix(1);
+        Ok(if other.sign {
+            (add_ix(&q, &one), sub_ix(&r, other))
+        } else {
+            (sub_ix(&q, &one), add_ix(&r, other))
+        })
+    }
+
+    pub fn div_trunc(&self, other: &ix) -> ix {
+        expect_divisor(self.checked_div_rem_trunc(other)).0
+    }
+
+    pub fn rem_trunc(&self, other: &ix) -> ix {
+        expect_divisor(self.checked_div_rem_trunc(other)).1
+    }
+
+    /// Same as `div_ix`.
+    pub fn div_floor(&self, other: &ix) -> ix {
+        div_ix(self, other)
+    }
+
+    /// Same as `rem_ix`.
+    pub fn rem_floor(&self, other: &ix) -> ix {
+        rem_ix(self, other)
+    }
+
+    pub fn div_euclid(&self, other: &ix) -> ix {
+        expect_divisor(self.checked_div_rem_euclid(other)).0
+    }
+
+    pub fn rem_euclid(&self, other: &ix) -> ix {
+        expect_divisor(self.checked_div_rem_euclid(other)).1
     }
-    r
 }
 
-pub fn h2i_ix(s: &str) -> ix {
+fn expect_divisor<T>(r: Result<T, IxError>) -> T {
+    r.unwrap_or_else(|e| panic!("{}", e))
+}
+
+/// Strict hex parser: surrounding whitespace, an optional leading `-` and an
+/// optional `0x` prefix, then at least one hex digit. Missing digits are
+/// `Empty` (`StraySign` after a `-`), and a second sign is `StraySign`.
+pub fn try_h2i_ix(s: &str) -> Result<ix, IxError> {
     let s = s.trim();
-    if s.is_empty() {
-        return zero_ix();
+    let (neg, mut body) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+
+    body = body.strip_prefix("0x").or_else(|| body.strip_prefix("0X")).unwrap_or(body);
+
+    if body.starts_with(['+', '-']) || (neg && body.is_empty()) {
+        return Err(IxError::Parse(ParseIxError::StraySign));
     }
+    let mut x = ix::from_str_radix(body, 16)?;
+    x.sign = neg && !x.vals.is_empty();
+    Ok(x)
+}
 
-    let (neg, mut body) = if s.starts_with('-') {
-        (true, &s[1..])
-    } else {
-        (false, s)
+/// The lenient rules `h2i_ix` has always had: an empty body reads as zero, and
+/// each 16-digit chunk goes through `u64::from_str_radix`, so a `+` at the
+/// start of the body (or of a chunk) is accepted.
+fn h2i_lenient(s: &str) -> Result<ix, ParseIxError> {
+    let s = s.trim();
+    let (neg, mut body) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
     };
 
     body = body.strip_prefix("0x").or_else(|| body.strip_prefix("0X")).unwrap_or(body);
 
-    if body.is_empty() {
-        return zero_ix();
+    if let Some(c) = body.chars().find(|c| !c.is_ascii()) {
+        return Err(ParseIxError::InvalidDigit(c));
     }
-
     let mut vals = vec![];
     let mut i = body.len();
     while i > 0 {
-        let start = if i >= 16 { i - 16 } else { 0 };
+        let start = i.saturating_sub(16);
         let chunk = &body[start..i];
-        let limb = u64::from_str_radix(chunk, 16).expect("bad hex input");
+        let limb = u64::from_str_radix(chunk, 16).map_err(|_| {
+            match chunk.chars().find(|c| !c.is_ascii_hexdigit()) {
+                Some('+' | '-') | None => ParseIxError::StraySign,
+                Some(c) => ParseIxError::InvalidDigit(c),
+            }
+        })?;
         vals.push(limb);
         i = start;
     }
     normalize_vals(&mut vals);
-    if vals.is_empty() {
-        zero_ix()
-    } else {
-        ix { sign: neg, vals }
-    }
+    let sign = neg && !vals.is_empty();
+    Ok(ix { sign, vals })
+}
+
+/// Parse hex, panicking on bad input. Kept lenient for existing callers; new
+/// code should prefer `try_h2i_ix`.
+pub fn h2i_ix(s: &str) -> ix {
+    h2i_lenient(s).expect("bad hex input")
 }
 
 pub fn ix_to_hex_string(x: &ix) -> String {
@@ -0,0 +1,74 @@
+// Carry-propagating building blocks shared by the heap magnitudes in lib.rs
+// and the fixed-width `Uint`. None of them allocate or normalize.
+
+/// `a + b + carry`, returning the low limb and the carry out (0 or 1).
+#[inline]
+pub(crate) fn adc(a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let sum = a as u128 + b as u128 + carry as u128;
+    (sum as u64, (sum >> 64) as u64)
+}
+
+/// `a - b - borrow`, returning the low limb and the borrow out (0 or 1).
+#[inline]
+pub(crate) fn sbb(a: u64, b: u64, borrow: u64) -> (u64, u64) {
+    let (t, b1) = a.overflowing_sub(b);
+    let (t, b2) = t.overflowing_sub(borrow);
+    (t, (b1 | b2) as u64)
+}
+
+/// `acc + a * b + carry`, which always fits in two limbs.
+#[inline]
+pub(crate) fn mac(acc: u64, a: u64, b: u64, carry: u64) -> (u64, u64) {
+    let t = acc as u128 + a as u128 * b as u128 + carry as u128;
+    (t as u64, (t >> 64) as u64)
+}
+
+/// `a += b` over `a.len()` limbs, with `b` zero-extended; returns the carry.
+pub(crate) fn add_assign_limbs(a: &mut [u64], b: &[u64]) -> u64 {
+    let mut carry = 0;
+    for (i, limb) in a.iter_mut().enumerate() {
+        let rhs = b.get(i).copied().unwrap_or(0);
+        if i >= b.len() && carry == 0 {
+            break;
+        }
+        (*limb, carry) = adc(*limb, rhs, carry);
+    }
+    carry
+}
+
+/// `a -= b` over `a.len()` limbs, with `b` zero-extended; returns the borrow.
+pub(crate) fn sub_assign_limbs(a: &mut [u64], b: &[u64]) -> u64 {
+    let mut borrow = 0;
+    for (i, limb) in a.iter_mut().enumerate() {
+        let rhs = b.get(i).copied().unwrap_or(0);
+        if i >= b.len() && borrow == 0 {
+            break;
+        }
+        (*limb, borrow) = sbb(*limb, rhs, borrow);
+    }
+    borrow
+}
+
+/// Schoolbook `out += a * b`, keeping only the low `out.len()` limbs.
+/// Returns whether any nonzero part of the product was cut off.
+pub(crate) fn mul_acc_limbs(out: &mut [u64], a: &[u64], b: &[u64]) -> bool {
+    let n = out.len();
+    let mut overflow = false;
+    for (i, &ai) in a.iter().enumerate() {
+        if ai == 0 {
+            continue;
+        }
+        let width = b.len().min(n.saturating_sub(i));
+        let mut carry = 0;
+        for (j, &bj) in b[..width].iter().enumerate() {
+            (out[i + j], carry) = mac(out[i + j], ai, bj, carry);
+        }
+        overflow |= b[width..].iter().any(|&v| v != 0);
+        let top = i + width;
+        if top < n {
+            carry = add_assign_limbs(&mut out[top..], &[carry]);
+        }
+        overflow |= carry != 0;
+    }
+    overflow
+}
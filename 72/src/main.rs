@@ -1,19 +1,67 @@
 use std::env;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Write};
 use bignum::{
-    add_ix, sub_ix, mul_ix, div_ix, rem_ix, h2i_ix, see_ix,
+    add_ix, sub_ix, mul_ix, div_ix, rem_ix, h2i_ix, see_ix, ix_to_hex_string, Calculator,
+    RandomSource, RsaPrivateKey, RsaPublicKey,
 };
 
+const USAGE: &str = "Usage: cargo run <hex1> <hex2> <OP>\n       cargo run [--hex] [FILE | -]\n       cargo run rsa <COMMAND> ...";
+
+const RSA_USAGE: &str = "\
+Usage: cargo run rsa keygen <BITS> <PRIVATE> <PUBLIC>
+       cargo run rsa encrypt [--pkcs1] <PUBLIC> <IN> <OUT>
+       cargo run rsa decrypt [--pkcs1] <PRIVATE> <IN> <OUT>
+       cargo run rsa sign <PRIVATE> <IN> <SIG>
+       cargo run rsa verify <PUBLIC> <IN> <SIG>
+Encryption uses OAEP unless --pkcs1 is given. IN and OUT may be - for
+stdin and stdout.";
 
 fn main() {
     let args: Vec<String> = env::args().collect();
-    if args.len() != 4 {
-        eprintln!("Usage: cargo run <hex1> <hex2> <OP>");
+    if args.get(1).is_some_and(|a| a == "rsa") {
+        if let Err(e) = rsa_command(&args[2..]) {
+            eprintln!("error: {}", e);
+            std::process::exit(1);
+        }
+        return;
+    }
+    if args.len() == 4 {
+        binary_op(&args[1], &args[2], &args[3]);
+        return;
+    }
+
+    let mut hex = false;
+    let mut path = None;
+    for arg in &args[1..] {
+        match arg.as_str() {
+            "--hex" => hex = true,
+            _ if path.is_none() => path = Some(arg.clone()),
+            _ => {
+                eprintln!("{}", USAGE);
+                std::process::exit(1);
+            }
+        }
+    }
+
+    let input: Box<dyn BufRead> = match path.as_deref() {
+        None | Some("-") => Box::new(io::stdin().lock()),
+        Some(p) => match File::open(p) {
+            Ok(f) => Box::new(BufReader::new(f)),
+            Err(e) => {
+                eprintln!("{}: {}", p, e);
+                std::process::exit(1);
+            }
+        },
+    };
+    let interactive = path.is_none() && io::stdin().is_terminal();
+    if !run_script(input, hex, interactive) {
         std::process::exit(1);
     }
+}
 
-    let a_hex = &args[1];
-    let b_hex = &args[2];
-    let op = &args[3].to_uppercase();
+fn binary_op(a_hex: &str, b_hex: &str, op: &str) {
+    let op = &op.to_uppercase();
 
     let a = h2i_ix(a_hex);
     let b = h2i_ix(b_hex);
@@ -34,3 +82,148 @@ fn main() {
     println!();
 }
 
+/// Evaluate each line, printing expression results. Errors are reported with
+/// their line number and do not stop the run; returns whether all lines
+/// succeeded.
+fn run_script(input: Box<dyn BufRead>, hex: bool, interactive: bool) -> bool {
+    let mut calc = Calculator::new();
+    let mut ok = true;
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    let mut lines = input.lines().enumerate();
+    loop {
+        if interactive {
+            print!("> ");
+            let _ = io::stdout().flush();
+        }
+        let Some((i, line)) = lines.next() else {
+            break;
+        };
+        let line = match line {
+            Ok(line) => line,
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return false;
+            }
+        };
+        match calc.eval_line(&line) {
+            Ok(Some(v)) => {
+                let text = if hex { ix_to_hex_string(&v) } else { v.to_string() };
+                if writeln!(out, "{}", text).is_err() {
+                    return ok;
+                }
+            }
+            Ok(None) => {}
+            Err(e) => {
+                ok = false;
+                if interactive {
+                    eprintln!("error: {}", e);
+                } else {
+                    eprintln!("error: line {}: {}", i + 1, e);
+                }
+            }
+        }
+    }
+    if interactive {
+        println!();
+    }
+    ok
+}
+
+/// Random limbs from the operating system.
+struct OsRandom(File);
+
+impl RandomSource for OsRandom {
+    fn next_u64(&mut self) -> u64 {
+        let mut buf = [0u8; 8];
+        self.0
+            .read_exact(&mut buf)
+            .expect("reading /dev/urandom failed");
+        u64::from_le_bytes(buf)
+    }
+}
+
+fn os_random() -> Result<OsRandom, String> {
+    File::open("/dev/urandom")
+        .map(OsRandom)
+        .map_err(|e| format!("/dev/urandom: {}", e))
+}
+
+fn read_input(path: &str) -> Result<Vec<u8>, String> {
+    let mut data = Vec::new();
+    let result = if path == "-" {
+        io::stdin().read_to_end(&mut data)
+    } else {
+        File::open(path).and_then(|mut f| f.read_to_end(&mut data))
+    };
+    result.map_err(|e| format!("{}: {}", path, e))?;
+    Ok(data)
+}
+
+fn write_output(path: &str, data: &[u8]) -> Result<(), String> {
+    let result = if path == "-" {
+        io::stdout().write_all(data)
+    } else {
+        std::fs::write(path, data)
+    };
+    result.map_err(|e| format!("{}: {}", path, e))
+}
+
+fn read_text(path: &str) -> Result<String, String> {
+    String::from_utf8(read_input(path)?).map_err(|_| format!("{}: not a key file", path))
+}
+
+fn rsa_command(args: &[String]) -> Result<(), String> {
+    let pkcs1 = args.iter().any(|a| a == "--pkcs1");
+    let args: Vec<&str> = args
+        .iter()
+        .map(String::as_str)
+        .filter(|&a| a != "--pkcs1")
+        .collect();
+    let err = |e: bignum::RsaError| e.to_string();
+    match args[..] {
+        ["keygen", bits, private, public] => {
+            let bits: usize = match bits.parse() {
+                Ok(b) if b >= 512 => b,
+                _ => return Err(format!("invalid key size {:?}; need at least 512", bits)),
+            };
+            let key = RsaPrivateKey::generate(bits, &mut os_random()?);
+            write_output(private, key.to_pem().as_bytes())?;
+            write_output(public, key.public_key().to_pem().as_bytes())
+        }
+        ["encrypt", key, input, output] => {
+            let key = RsaPublicKey::from_pem(&read_text(key)?).map_err(err)?;
+            let msg = read_input(input)?;
+            let mut rng = os_random()?;
+            let ct = if pkcs1 {
+                key.encrypt_pkcs1v15(&msg, &mut rng)
+            } else {
+                key.encrypt_oaep(&msg, b"", &mut rng)
+            };
+            write_output(output, &ct.map_err(err)?)
+        }
+        ["decrypt", key, input, output] => {
+            let key = RsaPrivateKey::from_pem(&read_text(key)?).map_err(err)?;
+            let ct = read_input(input)?;
+            let msg = if pkcs1 {
+                key.decrypt_pkcs1v15(&ct)
+            } else {
+                key.decrypt_oaep(&ct, b"")
+            };
+            write_output(output, &msg.map_err(err)?)
+        }
+        ["sign", key, input, sig] => {
+            let key = RsaPrivateKey::from_pem(&read_text(key)?).map_err(err)?;
+            let sig_bytes = key.sign(&read_input(input)?).map_err(err)?;
+            write_output(sig, &sig_bytes)
+        }
+        ["verify", key, input, sig] => {
+            let key = RsaPublicKey::from_pem(&read_text(key)?).map_err(err)?;
+            key.verify(&read_input(input)?, &read_input(sig)?)
+                .map_err(err)?;
+            println!("Signature OK");
+            Ok(())
+        }
+        _ => Err(RSA_USAGE.to_string()),
+    }
+}
@@ -0,0 +1,234 @@
+use crate::barrett::BarrettCtx;
+use crate::mont::MontgomeryCtx;
+use crate::{
+    add_ix, bit_len, cmp_mag, div_ix, div_rem_mag, ix, mul_ix, rem_ix, sqr_ix, sub_ix, u64_to_ix,
+    zero_ix,
+};
+
+fn one() -> ix {
+    u64_to_ix(1)
+}
+
+fn abs_ix(x: &ix) -> ix {
+    ix {
+        sign: false,
+        vals: x.vals.clone(),
+    }
+}
+
+/// Test bit `k` of a magnitude.
+fn mag_bit(vals: &[u64], k: usize) -> bool {
+    vals.get(k / 64).is_some_and(|limb| limb >> (k % 64) & 1 == 1)
+}
+
+/// Window width for sliding-window exponentiation, by exponent size.
+fn window_bits(exp_bits: usize) -> usize {
+    match exp_bits {
+        0..=24 => 1,
+        25..=80 => 3,
+        81..=240 => 4,
+        241..=672 => 5,
+        _ => 6,
+    }
+}
+
+/// `2^k` as an `ix`.
+fn pow2(k: usize) -> ix {
+    let mut vals = vec![0u64; k / 64 + 1];
+    vals[k / 64] = 1 << (k % 64);
+    ix { sign: false, vals }
+}
+
+/// Left-to-right sliding-window exponentiation over any multiplication:
+/// `one` is the identity and `mul` / `sqr` must already reduce.
+pub(crate) fn window_pow(
+    base: &ix,
+    exp: &[u64],
+    one: ix,
+    mul: impl Fn(&ix, &ix) -> ix,
+    sqr: impl Fn(&ix) -> ix,
+) -> ix {
+    // Precompute base^1, base^3, ..., base^(2^w - 1).
+    let bits = bit_len(exp);
+    let w = window_bits(bits);
+    let base_sq = sqr(base);
+    let mut odd = vec![base.clone()];
+    for i in 1..1 << (w - 1) {
+        odd.push(mul(&odd[i - 1], &base_sq));
+    }
+
+    // Scan from the top: square through zeros, and consume runs that
+    // start and end with a one in a single multiplication.
+    let mut result = one;
+    let mut i = bits as isize - 1;
+    while i >= 0 {
+        if !mag_bit(exp, i as usize) {
+            result = sqr(&result);
+            i -= 1;
+            continue;
+        }
+        let mut j = std::cmp::max(i - w as isize + 1, 0);
+        while !mag_bit(exp, j as usize) {
+            j += 1;
+        }
+        let mut value = 0usize;
+        for k in (j..=i).rev() {
+            result = sqr(&result);
+            value = value << 1 | mag_bit(exp, k as usize) as usize;
+        }
+        result = mul(&result, &odd[value >> 1]);
+        i = j - 1;
+    }
+    result
+}
+
+impl ix {
+    /// `self` raised to `exp`, by square-and-multiply.
+    pub fn pow(&self, exp: u32) -> ix {
+        let mut result = one();
+        for i in (0..32 - exp.leading_zeros()).rev() {
+            result = sqr_ix(&result);
+            if exp >> i & 1 == 1 {
+                result = mul_ix(&result, self);
+            }
+        }
+        result
+    }
+
+    /// `self^exp mod m` with the result taking the sign of `m`, as `rem_ix`
+    /// does. A negative `exp` uses the modular inverse of `self`.
+    ///
+    /// Panics if `m` is zero, or if `exp` is negative and `self` has no
+    /// inverse modulo `m`.
+    pub fn pow_mod(&self, exp: &ix, m: &ix) -> ix {
+        if m.vals.is_empty() {
+            panic!("pow_mod with zero modulus");
+        }
+        let base = if exp.sign {
+            self.mod_inverse(m)
+                .expect("pow_mod with negative exponent of non-invertible base")
+        } else {
+            rem_ix(self, m)
+        };
+        let exp = abs_ix(exp);
+        if m.vals == [1] {
+            return zero_ix();
+        }
+
+        // Odd moduli avoid a full division per step via Montgomery form,
+        // even ones via Barrett reduction. Both contexts work on |m|, so
+        // fold the result back to m's sign.
+        let abs_m = abs_ix(m);
+        let r = if m.vals[0] & 1 == 1 {
+            MontgomeryCtx::new(&abs_m).unwrap().pow(&base, &exp)
+        } else {
+            BarrettCtx::new(&abs_m).unwrap().pow(&base, &exp)
+        };
+        if m.sign && !r.vals.is_empty() {
+            add_ix(&r, m)
+        } else {
+            r
+        }
+    }
+
+    /// Greatest common divisor, always non-negative; `gcd(0, 0) == 0`.
+    pub fn gcd(&self, other: &ix) -> ix {
+        let mut a = self.vals.clone();
+        let mut b = other.vals.clone();
+        while !b.is_empty() {
+            let (_, r) = div_rem_mag(&a, &b);
+            a = b;
+            b = r;
+        }
+        ix { sign: false, vals: a }
+    }
+
+    /// Extended Euclid: `(g, x, y)` with `self * x + other * y == g` and
+    /// `g == gcd(self, other)`.
+    pub fn egcd(&self, other: &ix) -> (ix, ix, ix) {
+        let (mut old_r, mut r) = (self.clone(), other.clone());
+        let (mut old_s, mut s) = (one(), zero_ix());
+        let (mut old_t, mut t) = (zero_ix(), one());
+        while !r.vals.is_empty() {
+            let q = div_ix(&old_r, &r);
+            let next_r = sub_ix(&old_r, &mul_ix(&q, &r));
+            old_r = std::mem::replace(&mut r, next_r);
+            let next_s = sub_ix(&old_s, &mul_ix(&q, &s));
+            old_s = std::mem::replace(&mut s, next_s);
+            let next_t = sub_ix(&old_t, &mul_ix(&q, &t));
+            old_t = std::mem::replace(&mut t, next_t);
+        }
+        if old_r.sign {
+            old_r.sign = false;
+            old_s = -old_s;
+            old_t = -old_t;
+        }
+        (old_r, old_s, old_t)
+    }
+
+    /// The `x` with `self * x ≡ 1 (mod m)`, reduced like `rem_ix(x, m)`,
+    /// or `None` if `m` is zero or `gcd(self, m) != 1`.
+    pub fn mod_inverse(&self, m: &ix) -> Option<ix> {
+        if m.vals.is_empty() {
+            return None;
+        }
+        let (g, x, _) = rem_ix(self, m).egcd(m);
+        if g.vals != [1] {
+            return None;
+        }
+        Some(rem_ix(&x, m))
+    }
+
+    /// Least common multiple, always non-negative; zero if either input is.
+    pub fn lcm(&self, other: &ix) -> ix {
+        if self.vals.is_empty() || other.vals.is_empty() {
+            return zero_ix();
+        }
+        let g = self.gcd(other);
+        abs_ix(&mul_ix(&div_ix(&abs_ix(self), &g), other))
+    }
+
+    /// Floor of the square root. Panics on negative input.
+    pub fn isqrt(&self) -> ix {
+        self.nth_root(2)
+    }
+
+    /// Floor of the `n`th root, so negative inputs with odd `n` round
+    /// towards negative infinity like `div_ix`.
+    ///
+    /// Panics if `n` is zero, or if `n` is even and `self` is negative.
+    pub fn nth_root(&self, n: u32) -> ix {
+        assert!(n > 0, "zeroth root is undefined");
+        if self.sign {
+            assert!(n % 2 == 1, "even root of a negative number");
+            let r = abs_ix(self).nth_root(n);
+            // floor(-x^(1/n)) is one further down unless the root is exact.
+            let exact = r.pow(n).vals == self.vals;
+            let r = -r;
+            return if exact { r } else { sub_ix(&r, &one()) };
+        }
+        if self.vals.is_empty() || n == 1 {
+            return self.clone();
+        }
+        let bits = bit_len(&self.vals);
+        if bits <= n as usize {
+            return one();
+        }
+
+        // Newton's method from an overestimate decreases monotonically to
+        // the floor root.
+        let n_ix = u64_to_ix(n as u64);
+        let n_minus_1 = u64_to_ix(n as u64 - 1);
+        let mut x = pow2(bits.div_ceil(n as usize));
+        loop {
+            let y = div_ix(
+                &add_ix(&mul_ix(&n_minus_1, &x), &div_ix(self, &x.pow(n - 1))),
+                &n_ix,
+            );
+            if cmp_mag(&y.vals, &x.vals) >= 0 {
+                return x;
+            }
+            x = y;
+        }
+    }
+}
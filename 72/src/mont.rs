@@ -0,0 +1,123 @@
+use crate::modular::window_pow;
+use crate::{cmp_mag, div_rem_mag, ix, mul_mag, normalize_vals, rem_ix, sqr_mag, sub_mag};
+
+/// Precomputed state for Montgomery multiplication modulo a fixed odd `n`.
+///
+/// Values in Montgomery form are `a·R mod n` with `R = 2^(64·k)` for a
+/// `k`-limb modulus; `mul` and `sqr` take and return that form, so a chain
+/// of products needs only one conversion at each end.
+#[derive(Clone, Debug)]
+pub struct MontgomeryCtx {
+    n: Vec<u64>,
+    /// `-n^-1 mod 2^64`.
+    n_prime: u64,
+    /// `R^2 mod n`, used to convert into Montgomery form.
+    r2: Vec<u64>,
+}
+
+impl MontgomeryCtx {
+    /// Build a context for `modulus`, or `None` unless it is odd and > 1.
+    pub fn new(modulus: &ix) -> Option<MontgomeryCtx> {
+        if modulus.sign || modulus.vals.is_empty() || modulus.vals[0] & 1 == 0 {
+            return None;
+        }
+        if modulus.vals == [1] {
+            return None;
+        }
+        let n = modulus.vals.clone();
+
+        // Newton iteration doubles the number of correct low bits each step.
+        let mut inv: u64 = 1;
+        for _ in 0..6 {
+            inv = inv.wrapping_mul(2u64.wrapping_sub(n[0].wrapping_mul(inv)));
+        }
+
+        let mut r_squared = vec![0u64; 2 * n.len() + 1];
+        r_squared[2 * n.len()] = 1;
+        let (_, r2) = div_rem_mag(&r_squared, &n);
+        Some(MontgomeryCtx {
+            n,
+            n_prime: inv.wrapping_neg(),
+            r2,
+        })
+    }
+
+    pub fn modulus(&self) -> ix {
+        ix {
+            sign: false,
+            vals: self.n.clone(),
+        }
+    }
+
+    /// Montgomery reduction: `t · R^-1 mod n` for `t < n·R`.
+    fn redc(&self, t: &[u64]) -> Vec<u64> {
+        let k = self.n.len();
+        let mut a = vec![0u64; 2 * k + 1];
+        a[..t.len()].copy_from_slice(t);
+        for i in 0..k {
+            let u = a[i].wrapping_mul(self.n_prime);
+            let mut carry: u128 = 0;
+            for j in 0..k {
+                let s = a[i + j] as u128 + (u as u128) * (self.n[j] as u128) + carry;
+                a[i + j] = s as u64;
+                carry = s >> 64;
+            }
+            let mut idx = i + k;
+            while carry != 0 {
+                let s = a[idx] as u128 + carry;
+                a[idx] = s as u64;
+                carry = s >> 64;
+                idx += 1;
+            }
+        }
+        let mut out = a.split_off(k);
+        normalize_vals(&mut out);
+        if cmp_mag(&out, &self.n) >= 0 {
+            out = sub_mag(&out, &self.n);
+        }
+        out
+    }
+
+    fn wrap(vals: Vec<u64>) -> ix {
+        ix { sign: false, vals }
+    }
+
+    /// Convert `a` (any integer) into Montgomery form.
+    pub fn to_mont(&self, a: &ix) -> ix {
+        let a = rem_ix(a, &self.modulus());
+        Self::wrap(self.redc(&mul_mag(&a.vals, &self.r2)))
+    }
+
+    /// Convert a Montgomery-form value back to an ordinary residue.
+    pub fn from_mont(&self, a: &ix) -> ix {
+        Self::wrap(self.redc(&a.vals))
+    }
+
+    /// Product of two Montgomery-form values, in Montgomery form.
+    pub fn mul(&self, a: &ix, b: &ix) -> ix {
+        Self::wrap(self.redc(&mul_mag(&a.vals, &b.vals)))
+    }
+
+    /// Square of a Montgomery-form value, in Montgomery form.
+    pub fn sqr(&self, a: &ix) -> ix {
+        Self::wrap(self.redc(&sqr_mag(&a.vals)))
+    }
+
+    /// `base^exp mod n` for an ordinary `base` and non-negative `exp`,
+    /// returned as an ordinary residue in `[0, n)`.
+    pub fn pow(&self, base: &ix, exp: &ix) -> ix {
+        assert!(!exp.sign, "negative exponent");
+        let one = self.to_mont(&ix {
+            sign: false,
+            vals: vec![1],
+        });
+        let r = window_pow(
+            &self.to_mont(base),
+            &exp.vals,
+            one,
+            |a, b| self.mul(a, b),
+            |a| self.sqr(a),
+        );
+        self.from_mont(&r)
+    }
+}
@@ -0,0 +1,254 @@
+use crate::limb::mul_acc_limbs;
+use crate::{add_mag, div_rem_limb, ix, normalize_vals, sub_ix};
+
+/// Operands with at least this many limbs (the shorter one) use Karatsuba.
+pub const KARATSUBA_THRESHOLD: usize = 48;
+/// Operands with at least this many limbs (the shorter one) use Toom-3.
+pub const TOOM3_THRESHOLD: usize = 256;
+
+fn trim(v: &[u64]) -> &[u64] {
+    let mut n = v.len();
+    while n > 0 && v[n - 1] == 0 {
+        n -= 1;
+    }
+    &v[..n]
+}
+
+/// `out[offset..] += x`, growing `out` as needed.
+fn add_into(out: &mut Vec<u64>, x: &[u64], offset: usize) {
+    if out.len() < offset + x.len() {
+        out.resize(offset + x.len(), 0);
+    }
+    let mut carry = 0u128;
+    let mut i = 0;
+    while i < x.len() || carry != 0 {
+        if offset + i >= out.len() {
+            out.push(0);
+        }
+        let add = if i < x.len() { x[i] as u128 } else { 0 };
+        let sum = out[offset + i] as u128 + add + carry;
+        out[offset + i] = sum as u64;
+        carry = sum >> 64;
+        i += 1;
+    }
+}
+
+/// `a -= b` for magnitudes with `a >= b`.
+fn sub_in_place(a: &mut Vec<u64>, b: &[u64]) {
+    let mut borrow = false;
+    for i in 0..a.len() {
+        let rhs = if i < b.len() { b[i] } else { 0 };
+        if i >= b.len() && !borrow {
+            break;
+        }
+        let (t, b1) = a[i].overflowing_sub(rhs);
+        let (t, b2) = t.overflowing_sub(borrow as u64);
+        a[i] = t;
+        borrow = b1 || b2;
+    }
+    normalize_vals(a);
+}
+
+/// O(n·m) product; the reference the faster routines are checked against.
+pub fn mul_schoolbook(a: &[u64], b: &[u64]) -> Vec<u64> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let mut out = vec![0u64; a.len() + b.len()];
+    mul_acc_limbs(&mut out, a, b);
+    normalize_vals(&mut out);
+    out
+}
+
+/// Schoolbook squaring: each cross product is computed once and doubled.
+pub fn sqr_schoolbook(a: &[u64]) -> Vec<u64> {
+    let n = a.len();
+    if n == 0 {
+        return vec![];
+    }
+    let mut out = vec![0u64; 2 * n];
+    for i in 0..n {
+        let mut carry: u128 = 0;
+        for j in i + 1..n {
+            let prod = (a[i] as u128) * (a[j] as u128) + (out[i + j] as u128) + carry;
+            out[i + j] = prod as u64;
+            carry = prod >> 64;
+        }
+        out[i + n] = carry as u64;
+    }
+    let mut top = 0u64;
+    for limb in out.iter_mut() {
+        let next = *limb >> 63;
+        *limb = (*limb << 1) | top;
+        top = next;
+    }
+    let mut carry: u128 = 0;
+    for i in 0..n {
+        let sq = (a[i] as u128) * (a[i] as u128);
+        let lo = out[2 * i] as u128 + (sq as u64) as u128 + carry;
+        out[2 * i] = lo as u64;
+        let hi = out[2 * i + 1] as u128 + (sq >> 64) + (lo >> 64);
+        out[2 * i + 1] = hi as u64;
+        carry = hi >> 64;
+    }
+    normalize_vals(&mut out);
+    out
+}
+
+/// Product of two magnitudes, picking schoolbook, Karatsuba or Toom-3 by
+/// the size of the shorter operand.
+pub fn mul_mag(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let (a, b) = (trim(a), trim(b));
+    let (a, b) = if a.len() >= b.len() { (a, b) } else { (b, a) };
+    if b.len() < KARATSUBA_THRESHOLD {
+        return mul_schoolbook(a, b);
+    }
+    if a.len() >= 2 * b.len() {
+        // Very unbalanced: multiply b by each b-sized slice of a.
+        let mut out = vec![];
+        for (i, chunk) in a.chunks(b.len()).enumerate() {
+            add_into(&mut out, &mul_mag(chunk, b), i * b.len());
+        }
+        normalize_vals(&mut out);
+        return out;
+    }
+    if b.len() < TOOM3_THRESHOLD {
+        karatsuba(a, b)
+    } else {
+        toom3(a, b)
+    }
+}
+
+/// Square of a magnitude, using the squaring variant of each algorithm.
+pub fn sqr_mag(a: &[u64]) -> Vec<u64> {
+    let a = trim(a);
+    if a.len() < KARATSUBA_THRESHOLD {
+        sqr_schoolbook(a)
+    } else if a.len() < TOOM3_THRESHOLD {
+        karatsuba_sqr(a)
+    } else {
+        toom3_sqr(a)
+    }
+}
+
+/// (a1·B + a0)(b1·B + b0) with the middle term from (a0 + a1)(b0 + b1).
+fn karatsuba(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let k = a.len().div_ceil(2);
+    let (a0, a1) = (trim(&a[..k]), &a[k..]);
+    let (b0, b1) = if b.len() > k {
+        (trim(&b[..k]), &b[k..])
+    } else {
+        (b, &b[b.len()..])
+    };
+    let z0 = mul_mag(a0, b0);
+    let z2 = mul_mag(a1, b1);
+    let mut z1 = mul_mag(&add_mag(a0, a1), &add_mag(b0, b1));
+    sub_in_place(&mut z1, &z0);
+    sub_in_place(&mut z1, &z2);
+
+    let mut out = z0;
+    add_into(&mut out, &z1, k);
+    add_into(&mut out, &z2, 2 * k);
+    normalize_vals(&mut out);
+    out
+}
+
+fn karatsuba_sqr(a: &[u64]) -> Vec<u64> {
+    let k = a.len().div_ceil(2);
+    let (a0, a1) = (trim(&a[..k]), &a[k..]);
+    let z0 = sqr_mag(a0);
+    let z2 = sqr_mag(a1);
+    let mut z1 = sqr_mag(&add_mag(a0, a1));
+    sub_in_place(&mut z1, &z0);
+    sub_in_place(&mut z1, &z2);
+
+    let mut out = z0;
+    add_into(&mut out, &z1, k);
+    add_into(&mut out, &z2, 2 * k);
+    normalize_vals(&mut out);
+    out
+}
+
+fn signed(vals: Vec<u64>, sign: bool) -> ix {
+    ix {
+        sign: sign && !vals.is_empty(),
+        vals,
+    }
+}
+
+fn mag(v: &[u64]) -> ix {
+    signed(trim(v).to_vec(), false)
+}
+
+fn mul_signed(x: &ix, y: &ix) -> ix {
+    signed(mul_mag(&x.vals, &y.vals), x.sign ^ y.sign)
+}
+
+fn sqr_signed(x: &ix) -> ix {
+    signed(sqr_mag(&x.vals), false)
+}
+
+fn div_exact(x: &ix, d: u64) -> ix {
+    let (q, r) = div_rem_limb(&x.vals, d);
+    debug_assert_eq!(r, 0);
+    signed(q, x.sign)
+}
+
+fn shl1(x: &ix) -> ix {
+    crate::add_ix(x, x)
+}
+
+/// Split into three `k`-limb pieces, low first.
+fn split3(a: &[u64], k: usize) -> [ix; 3] {
+    let piece = |i: usize| {
+        let lo = std::cmp::min(i * k, a.len());
+        let hi = std::cmp::min((i + 1) * k, a.len());
+        mag(&a[lo..hi])
+    };
+    [piece(0), piece(1), piece(2)]
+}
+
+/// Values at 0, 1, -1, -2 and infinity.
+fn toom3_eval(p: &[ix; 3]) -> [ix; 5] {
+    let [p0, p1, p2] = p;
+    let t = crate::add_ix(p0, p2);
+    let at_1 = crate::add_ix(&t, p1);
+    let at_m1 = sub_ix(&t, p1);
+    let at_m2 = sub_ix(&shl1(&crate::add_ix(&at_m1, p2)), p0);
+    [p0.clone(), at_1, at_m1, at_m2, p2.clone()]
+}
+
+/// Bodrato's interpolation sequence for the point set 0, 1, -1, -2, inf.
+fn toom3_interpolate(r: [ix; 5], k: usize) -> Vec<u64> {
+    let [r0, r_1, r_m1, r_m2, r_inf] = r;
+    let mut r3 = div_exact(&sub_ix(&r_m2, &r_1), 3);
+    let mut r1 = div_exact(&sub_ix(&r_1, &r_m1), 2);
+    let mut r2 = sub_ix(&r_m1, &r0);
+    r3 = crate::add_ix(&div_exact(&sub_ix(&r2, &r3), 2), &shl1(&r_inf));
+    r2 = sub_ix(&crate::add_ix(&r2, &r1), &r_inf);
+    r1 = sub_ix(&r1, &r3);
+
+    // Every coefficient of a product of non-negative polynomials is >= 0.
+    let mut out = r0.vals;
+    for (i, c) in [r1, r2, r3, r_inf].iter().enumerate() {
+        debug_assert!(!c.sign);
+        add_into(&mut out, &c.vals, (i + 1) * k);
+    }
+    normalize_vals(&mut out);
+    out
+}
+
+fn toom3(a: &[u64], b: &[u64]) -> Vec<u64> {
+    let k = a.len().div_ceil(3);
+    let pa = toom3_eval(&split3(a, k));
+    let pb = toom3_eval(&split3(b, k));
+    let r = std::array::from_fn(|i| mul_signed(&pa[i], &pb[i]));
+    toom3_interpolate(r, k)
+}
+
+fn toom3_sqr(a: &[u64]) -> Vec<u64> {
+    let k = a.len().div_ceil(3);
+    let pa = toom3_eval(&split3(a, k));
+    let r = std::array::from_fn(|i| sqr_signed(&pa[i]));
+    toom3_interpolate(r, k)
+}
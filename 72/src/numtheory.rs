@@ -0,0 +1,214 @@
+use crate::limb::mac;
+use crate::{add_ix, bit_len, cmp_mag, div_ix, ix, mul_ix, rem_ix, sqr_ix, sub_ix, u64_to_ix};
+
+fn one() -> ix {
+    u64_to_ix(1)
+}
+
+/// Multiply a magnitude by a single limb in place.
+fn mul_limb_in_place(vals: &mut Vec<u64>, m: u64) {
+    let mut carry = 0;
+    for v in vals.iter_mut() {
+        (*v, carry) = mac(0, *v, m, carry);
+    }
+    if carry != 0 {
+        vals.push(carry);
+    }
+}
+
+/// Product of `factors`, split in halves so that the big multiplications
+/// see operands of similar size and reach the Karatsuba and Toom ranges.
+fn product(factors: &[u64]) -> ix {
+    if factors.len() <= 16 {
+        let mut vals = vec![1];
+        for &f in factors {
+            mul_limb_in_place(&mut vals, f);
+        }
+        return if factors.contains(&0) {
+            u64_to_ix(0)
+        } else {
+            ix { sign: false, vals }
+        };
+    }
+    let (lo, hi) = factors.split_at(factors.len() / 2);
+    mul_ix(&product(lo), &product(hi))
+}
+
+/// The primes up to and including `n`.
+fn primes_up_to(n: u64) -> Vec<u64> {
+    let n = usize::try_from(n).expect("sieve bound exceeds the address space");
+    let mut composite = vec![false; n + 1];
+    let mut out = Vec::new();
+    for i in 2..=n {
+        if composite[i] {
+            continue;
+        }
+        out.push(i as u64);
+        for j in (i.saturating_mul(i)..=n).step_by(i) {
+            composite[j] = true;
+        }
+    }
+    out
+}
+
+/// `n! / (n/2)!^2`, the swinging factorial, from its prime factorisation.
+/// Each prime power it contains is at most `n`, so fits a limb.
+fn swing(n: u64, primes: &[u64]) -> ix {
+    let mut factors = Vec::new();
+    for &p in primes.iter().take_while(|&&p| p <= n) {
+        let mut pe = 1;
+        let mut q = n / p;
+        while q > 0 {
+            if q & 1 == 1 {
+                pe *= p;
+            }
+            q /= p;
+        }
+        if pe > 1 {
+            factors.push(pe);
+        }
+    }
+    product(&factors)
+}
+
+fn factorial_swing(n: u64, primes: &[u64]) -> ix {
+    if n < 2 {
+        return one();
+    }
+    mul_ix(&sqr_ix(&factorial_swing(n / 2, primes)), &swing(n, primes))
+}
+
+/// Exponent of the prime `p` in `C(n, k)`: by Kummer's theorem, the number
+/// of borrows when subtracting `k` from `n` in base `p`.
+fn binomial_exponent(mut n: u64, mut k: u64, p: u64) -> u32 {
+    let mut borrows = 0;
+    let mut borrow = 0;
+    while n > 0 {
+        let (nd, kd) = (n % p, k % p + borrow);
+        borrow = (kd > nd) as u64;
+        borrows += borrow as u32;
+        n /= p;
+        k /= p;
+    }
+    borrows
+}
+
+impl ix {
+    /// `n!`, by Luschny's prime swing: `n! = (n/2)!^2 * swing(n)`, with each
+    /// swing assembled from prime powers by a balanced product tree.
+    pub fn factorial(n: u64) -> ix {
+        factorial_swing(n, &primes_up_to(n))
+    }
+
+    /// The binomial coefficient `C(n, k)`, zero when `k > n`.
+    pub fn binomial(n: u64, k: u64) -> ix {
+        if k > n {
+            return u64_to_ix(0);
+        }
+        let k = k.min(n - k);
+        if k == 0 {
+            return one();
+        }
+        // With a small `k` the falling factorial is cheaper than sieving up
+        // to `n`; otherwise build the result from its prime factorisation.
+        if k < n / 64 {
+            let numer = product(&(n - k + 1..=n).collect::<Vec<_>>());
+            return div_ix(&numer, &ix::factorial(k));
+        }
+        let mut factors = Vec::new();
+        for p in primes_up_to(n) {
+            for _ in 0..binomial_exponent(n, k, p) {
+                factors.push(p);
+            }
+        }
+        product(&factors)
+    }
+
+    /// The `n`th Fibonacci number, with `F(0) = 0` and `F(1) = 1`, by fast
+    /// doubling: `F(2m) = F(m) * (2F(m+1) - F(m))` and
+    /// `F(2m+1) = F(m)^2 + F(m+1)^2`.
+    pub fn fibonacci(n: u64) -> ix {
+        let (mut a, mut b) = (u64_to_ix(0), one());
+        for i in (0..64 - n.leading_zeros()).rev() {
+            let two_b = add_ix(&b, &b);
+            let even = mul_ix(&a, &sub_ix(&two_b, &a));
+            let odd = add_ix(&sqr_ix(&a), &sqr_ix(&b));
+            (a, b) = if n >> i & 1 == 1 {
+                let next = add_ix(&even, &odd);
+                (odd, next)
+            } else {
+                (even, odd)
+            };
+        }
+        a
+    }
+
+    /// Solve `x ≡ r (mod m)` for every `(r, m)` in `congruences`. Returns
+    /// `(x, l)` where `l` is the lcm of the moduli and `0 <= x < l`, so the
+    /// solutions are exactly `x + k * l`. The moduli need not be coprime;
+    /// `None` means the congruences contradict each other.
+    ///
+    /// Panics if any modulus is zero or negative.
+    pub fn crt(congruences: &[(ix, ix)]) -> Option<(ix, ix)> {
+        let (mut x, mut l) = (u64_to_ix(0), one());
+        for (r, m) in congruences {
+            assert!(
+                !m.sign && !m.vals.is_empty(),
+                "crt with non-positive modulus"
+            );
+            // x + l * t ≡ r (mod m) has a solution iff g | r - x, and then
+            // t ≡ (r - x) / g * inv(l / g) (mod m / g).
+            let (g, p, _) = l.egcd(m);
+            let diff = sub_ix(r, &x);
+            if !rem_ix(&diff, &g).vals.is_empty() {
+                return None;
+            }
+            let m_g = div_ix(m, &g);
+            let t = rem_ix(&mul_ix(&div_ix(&diff, &g), &p), &m_g);
+            x = add_ix(&x, &mul_ix(&l, &t));
+            l = mul_ix(&l, &m_g);
+            x = rem_ix(&x, &l);
+        }
+        Some((x, l))
+    }
+
+    /// Floor of the base-2 logarithm. Panics unless `self` is positive.
+    pub fn ilog2(&self) -> u64 {
+        assert!(
+            !self.sign && !self.vals.is_empty(),
+            "argument of integer logarithm must be positive"
+        );
+        bit_len(&self.vals) as u64 - 1
+    }
+
+    /// Floor of the logarithm in `base`, or `None` if `self` is not positive
+    /// or `base` is less than two.
+    pub fn checked_ilog(&self, base: &ix) -> Option<u64> {
+        if self.sign || self.vals.is_empty() || base.sign || cmp_mag(&base.vals, &[2]) < 0 {
+            return None;
+        }
+        // Square up through base^(2^i) until one exceeds self, then add the
+        // powers back from the largest down, keeping those that still fit.
+        let mut powers = vec![base.clone()];
+        while cmp_mag(&powers[powers.len() - 1].vals, &self.vals) <= 0 {
+            let next = sqr_ix(&powers[powers.len() - 1]);
+            powers.push(next);
+        }
+        let (mut log, mut acc) = (0u64, one());
+        for (i, p) in powers.iter().enumerate().rev() {
+            let next = mul_ix(&acc, p);
+            if cmp_mag(&next.vals, &self.vals) <= 0 {
+                acc = next;
+                log += 1 << i;
+            }
+        }
+        Some(log)
+    }
+
+    /// Floor of the logarithm in `base`. Panics if `self` is not positive or
+    /// `base` is less than two.
+    pub fn ilog(&self, base: &ix) -> u64 {
+        self.checked_ilog(base)
+            .expect("integer logarithm needs a positive argument and a base of at least 2")
+    }
+}
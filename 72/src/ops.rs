@@ -0,0 +1,295 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{
+    Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div,
+    DivAssign, Mul, MulAssign, Neg, Not, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub,
+    SubAssign,
+};
+
+use crate::radix::mag_to_str_radix;
+use crate::{
+    add_ix, and_ix, cmp_mag, div_ix, ix, mul_ix, not_ix, or_ix, rem_ix, shl_ix, shr_ix, sub_ix,
+    xor_ix, zero_ix,
+};
+
+// `/` and `%` use the same floor semantics as `div_ix` / `rem_ix`.
+macro_rules! forward_binop {
+    ($Trait:ident, $method:ident, $func:ident) => {
+        impl $Trait<&ix> for &ix {
+            type Output = ix;
+            fn $method(self, rhs: &ix) -> ix {
+                $func(self, rhs)
+            }
+        }
+
+        impl $Trait<ix> for &ix {
+            type Output = ix;
+            fn $method(self, rhs: ix) -> ix {
+                $func(self, &rhs)
+            }
+        }
+
+        impl $Trait<&ix> for ix {
+            type Output = ix;
+            fn $method(self, rhs: &ix) -> ix {
+                $func(&self, rhs)
+            }
+        }
+
+        impl $Trait<ix> for ix {
+            type Output = ix;
+            fn $method(self, rhs: ix) -> ix {
+                $func(&self, &rhs)
+            }
+        }
+    };
+}
+
+macro_rules! forward_assign {
+    ($Trait:ident, $method:ident, $func:ident) => {
+        impl $Trait<&ix> for ix {
+            fn $method(&mut self, rhs: &ix) {
+                *self = $func(self, rhs);
+            }
+        }
+
+        impl $Trait<ix> for ix {
+            fn $method(&mut self, rhs: ix) {
+                *self = $func(self, &rhs);
+            }
+        }
+    };
+}
+
+forward_binop!(Add, add, add_ix);
+forward_binop!(Sub, sub, sub_ix);
+forward_binop!(Mul, mul, mul_ix);
+forward_binop!(Div, div, div_ix);
+forward_binop!(Rem, rem, rem_ix);
+
+forward_binop!(BitAnd, bitand, and_ix);
+forward_binop!(BitOr, bitor, or_ix);
+forward_binop!(BitXor, bitxor, xor_ix);
+
+forward_assign!(AddAssign, add_assign, add_ix);
+forward_assign!(SubAssign, sub_assign, sub_ix);
+forward_assign!(MulAssign, mul_assign, mul_ix);
+forward_assign!(DivAssign, div_assign, div_ix);
+forward_assign!(RemAssign, rem_assign, rem_ix);
+forward_assign!(BitAndAssign, bitand_assign, and_ix);
+forward_assign!(BitOrAssign, bitor_assign, or_ix);
+forward_assign!(BitXorAssign, bitxor_assign, xor_ix);
+
+impl Not for ix {
+    type Output = ix;
+    fn not(self) -> ix {
+        not_ix(&self)
+    }
+}
+
+impl Not for &ix {
+    type Output = ix;
+    fn not(self) -> ix {
+        not_ix(self)
+    }
+}
+
+// `>>` floors like Python, so `-1 >> k == -1`.
+macro_rules! forward_shift {
+    ($Trait:ident, $method:ident, $AssignTrait:ident, $assign:ident, $func:ident) => {
+        impl $Trait<usize> for ix {
+            type Output = ix;
+            fn $method(self, k: usize) -> ix {
+                $func(&self, k)
+            }
+        }
+
+        impl $Trait<usize> for &ix {
+            type Output = ix;
+            fn $method(self, k: usize) -> ix {
+                $func(self, k)
+            }
+        }
+
+        impl $AssignTrait<usize> for ix {
+            fn $assign(&mut self, k: usize) {
+                *self = $func(self, k);
+            }
+        }
+    };
+}
+
+forward_shift!(Shl, shl, ShlAssign, shl_assign, shl_ix);
+forward_shift!(Shr, shr, ShrAssign, shr_assign, shr_ix);
+
+impl Neg for ix {
+    type Output = ix;
+    fn neg(mut self) -> ix {
+        self.sign = !self.sign && !self.vals.is_empty();
+        self
+    }
+}
+
+impl Neg for &ix {
+    type Output = ix;
+    fn neg(self) -> ix {
+        -self.clone()
+    }
+}
+
+impl Ord for ix {
+    fn cmp(&self, other: &ix) -> Ordering {
+        match (self.sign, other.sign) {
+            (false, true) => Ordering::Greater,
+            (true, false) => Ordering::Less,
+            (false, false) => cmp_mag(&self.vals, &other.vals).cmp(&0),
+            (true, true) => cmp_mag(&other.vals, &self.vals).cmp(&0),
+        }
+    }
+}
+
+impl PartialOrd for ix {
+    fn partial_cmp(&self, other: &ix) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn from_mag_u128(sign: bool, mag: u128) -> ix {
+    let mut vals = vec![mag as u64, (mag >> 64) as u64];
+    while vals.last() == Some(&0) {
+        vals.pop();
+    }
+    ix {
+        sign: sign && !vals.is_empty(),
+        vals,
+    }
+}
+
+impl From<u64> for ix {
+    fn from(v: u64) -> ix {
+        from_mag_u128(false, v as u128)
+    }
+}
+
+impl From<i64> for ix {
+    fn from(v: i64) -> ix {
+        from_mag_u128(v < 0, v.unsigned_abs() as u128)
+    }
+}
+
+impl From<u128> for ix {
+    fn from(v: u128) -> ix {
+        from_mag_u128(false, v)
+    }
+}
+
+impl From<i128> for ix {
+    fn from(v: i128) -> ix {
+        from_mag_u128(v < 0, v.unsigned_abs())
+    }
+}
+
+/// Error returned when an `ix` does not fit in the requested primitive.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TryFromIxError(pub(crate) ());
+
+impl fmt::Display for TryFromIxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ix out of range for target integer type")
+    }
+}
+
+impl std::error::Error for TryFromIxError {}
+
+/// The magnitude as a `u128`, or `None` if it needs more than two limbs.
+fn mag_u128(x: &ix) -> Option<u128> {
+    match x.vals.len() {
+        0 => Some(0),
+        1 => Some(x.vals[0] as u128),
+        2 => Some(x.vals[0] as u128 | (x.vals[1] as u128) << 64),
+        _ => None,
+    }
+}
+
+macro_rules! try_from_ix {
+    ($t:ty, |$sign:ident, $mag:ident| $convert:expr) => {
+        impl TryFrom<&ix> for $t {
+            type Error = TryFromIxError;
+            fn try_from(x: &ix) -> Result<$t, TryFromIxError> {
+                let $sign = x.sign;
+                let $mag = mag_u128(x).ok_or(TryFromIxError(()))?;
+                $convert.ok_or(TryFromIxError(()))
+            }
+        }
+
+        impl TryFrom<ix> for $t {
+            type Error = TryFromIxError;
+            fn try_from(x: ix) -> Result<$t, TryFromIxError> {
+                <$t>::try_from(&x)
+            }
+        }
+    };
+}
+
+try_from_ix!(u64, |sign, mag| (!sign).then_some(mag).and_then(|m| u64::try_from(m).ok()));
+try_from_ix!(u128, |sign, mag| (!sign).then_some(mag));
+try_from_ix!(i64, |sign, mag| {
+    if sign {
+        (mag <= 1u128 << 63).then(|| (mag as i64).wrapping_neg())
+    } else {
+        i64::try_from(mag).ok()
+    }
+});
+try_from_ix!(i128, |sign, mag| {
+    if sign {
+        (mag <= 1u128 << 127).then(|| (mag as i128).wrapping_neg())
+    } else {
+        i128::try_from(mag).ok()
+    }
+});
+
+fn mag_to_hex(vals: &[u64], upper: bool) -> String {
+    if vals.is_empty() {
+        return "0".to_string();
+    }
+    let mut out = String::new();
+    for (i, &limb) in vals.iter().rev().enumerate() {
+        match (i == 0, upper) {
+            (true, false) => out.push_str(&format!("{:x}", limb)),
+            (true, true) => out.push_str(&format!("{:X}", limb)),
+            (false, false) => out.push_str(&format!("{:016x}", limb)),
+            (false, true) => out.push_str(&format!("{:016X}", limb)),
+        }
+    }
+    out
+}
+
+impl fmt::Display for ix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.sign, "", &mag_to_str_radix(&self.vals, 10))
+    }
+}
+
+impl fmt::LowerHex for ix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.sign, "0x", &mag_to_hex(&self.vals, false))
+    }
+}
+
+impl fmt::UpperHex for ix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad_integral(!self.sign, "0x", &mag_to_hex(&self.vals, true))
+    }
+}
+
+impl std::iter::Sum for ix {
+    fn sum<I: Iterator<Item = ix>>(iter: I) -> ix {
+        iter.fold(zero_ix(), |acc, x| acc + x)
+    }
+}
+
+impl std::iter::Product for ix {
+    fn product<I: Iterator<Item = ix>>(iter: I) -> ix {
+        iter.fold(ix::from(1u64), |acc, x| acc * x)
+    }
+}
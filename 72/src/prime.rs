@@ -0,0 +1,335 @@
+use crate::{
+    add_ix, bit_len, cmp_mag, div_rem_limb, ix, mul_ix, normalize_vals, rem_ix, shr_mag, sqr_ix,
+    sub_ix, u64_to_ix,
+};
+
+/// Source of random limbs for prime generation and Miller–Rabin bases.
+/// Implement it over whatever RNG the caller already has.
+pub trait RandomSource {
+    fn next_u64(&mut self) -> u64;
+}
+
+const SMALL_PRIME_COUNT: usize = 168;
+
+/// The primes below 1000, sieved at compile time.
+const SMALL_PRIMES: [u64; SMALL_PRIME_COUNT] = {
+    let mut composite = [false; 1000];
+    let mut out = [0u64; SMALL_PRIME_COUNT];
+    let mut count = 0;
+    let mut i = 2;
+    while i < 1000 {
+        if !composite[i] {
+            out[count] = i as u64;
+            count += 1;
+            let mut j = i * i;
+            while j < 1000 {
+                composite[j] = true;
+                j += i;
+            }
+        }
+        i += 1;
+    }
+    out
+};
+
+fn one() -> ix {
+    u64_to_ix(1)
+}
+
+fn mul_mod_u64(a: u64, b: u64, n: u64) -> u64 {
+    ((a as u128 * b as u128) % n as u128) as u64
+}
+
+fn pow_mod_u64(mut base: u64, mut exp: u64, n: u64) -> u64 {
+    let mut result = 1 % n;
+    base %= n;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = mul_mod_u64(result, base, n);
+        }
+        base = mul_mod_u64(base, base, n);
+        exp >>= 1;
+    }
+    result
+}
+
+/// Deterministic primality for any `u64`: Miller–Rabin with the first
+/// twelve prime bases has no strong pseudoprimes below 3.3·10^24.
+pub fn is_prime_u64(n: u64) -> bool {
+    if n < 2 {
+        return false;
+    }
+    for &p in &SMALL_PRIMES[..12] {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+    let s = (n - 1).trailing_zeros();
+    let d = (n - 1) >> s;
+    'bases: for &a in &SMALL_PRIMES[..12] {
+        let mut x = pow_mod_u64(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod_u64(x, x, n);
+            if x == n - 1 {
+                continue 'bases;
+            }
+        }
+        return false;
+    }
+    true
+}
+
+/// A uniformly random non-negative `ix` below `2^bits`.
+pub fn random_bits(bits: usize, rng: &mut impl RandomSource) -> ix {
+    let mut vals: Vec<u64> = (0..bits.div_ceil(64)).map(|_| rng.next_u64()).collect();
+    if let Some(top) = vals.last_mut()
+        && !bits.is_multiple_of(64)
+    {
+        *top &= (1u64 << (bits % 64)) - 1;
+    }
+    normalize_vals(&mut vals);
+    ix { sign: false, vals }
+}
+
+/// A uniformly random `ix` in `[0, bound)`, by rejection sampling.
+/// Panics unless `bound` is positive.
+pub fn random_below(bound: &ix, rng: &mut impl RandomSource) -> ix {
+    assert!(!bound.sign && !bound.vals.is_empty(), "bound must be positive");
+    let bits = bit_len(&bound.vals);
+    loop {
+        let x = random_bits(bits, rng);
+        if cmp_mag(&x.vals, &bound.vals) < 0 {
+            return x;
+        }
+    }
+}
+
+/// A random prime of exactly `bits` bits. Panics if `bits < 2`.
+pub fn random_prime(bits: usize, rng: &mut impl RandomSource) -> ix {
+    assert!(bits >= 2, "no primes with fewer than 2 bits");
+    loop {
+        let mut candidate = random_bits(bits, rng);
+        let top = bits - 1;
+        if candidate.vals.len() <= top / 64 {
+            candidate.vals.resize(top / 64 + 1, 0);
+        }
+        candidate.vals[top / 64] |= 1 << (top % 64);
+        if bits > 2 {
+            candidate.vals[0] |= 1;
+        }
+        if candidate.is_probable_prime() {
+            return candidate;
+        }
+    }
+}
+
+/// Outcome of trial division by the small-prime table.
+enum Trial {
+    Prime,
+    Composite,
+    Unknown,
+}
+
+fn trial_division(n: &ix) -> Trial {
+    for &p in &SMALL_PRIMES {
+        if n.vals == [p] {
+            return Trial::Prime;
+        }
+        if div_rem_limb(&n.vals, p).1 == 0 {
+            return Trial::Composite;
+        }
+    }
+    Trial::Unknown
+}
+
+/// Number of low zero bits of a non-zero magnitude.
+fn trailing_zeros_mag(vals: &[u64]) -> usize {
+    let i = vals.iter().position(|&v| v != 0).unwrap();
+    i * 64 + vals[i].trailing_zeros() as usize
+}
+
+/// `(x / 2) mod n` for odd `n` and `x` in `[0, n)`.
+fn half_mod(x: &ix, n: &ix) -> ix {
+    let x = if x.vals.first().is_some_and(|v| v & 1 == 1) {
+        add_ix(x, n)
+    } else {
+        x.clone()
+    };
+    ix {
+        sign: false,
+        vals: shr_mag(&x.vals, 1),
+    }
+}
+
+impl ix {
+    /// Miller–Rabin with `rounds` random bases; a composite passes each
+    /// round with probability at most 1/4.
+    pub fn miller_rabin(&self, rounds: usize, rng: &mut impl RandomSource) -> bool {
+        if self.sign || self.vals.is_empty() || self.vals[0] & 1 == 0 || self.vals == [1] {
+            return self.vals == [2] && !self.sign;
+        }
+        if self.vals == [3] {
+            return true;
+        }
+        let three = u64_to_ix(3);
+        for _ in 0..rounds {
+            // Bases in [2, n - 2].
+            let a = add_ix(&random_below(&sub_ix(self, &three), rng), &u64_to_ix(2));
+            if !self.strong_probable_prime(&a) {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// One Miller–Rabin round to base `a`, for odd `self > 2`.
+    fn strong_probable_prime(&self, a: &ix) -> bool {
+        let n_minus_1 = sub_ix(self, &one());
+        let s = trailing_zeros_mag(&n_minus_1.vals);
+        let d = ix {
+            sign: false,
+            vals: shr_mag(&n_minus_1.vals, s),
+        };
+        let mut x = a.pow_mod(&d, self);
+        if x.vals == [1] || x == n_minus_1 {
+            return true;
+        }
+        for _ in 1..s {
+            x = rem_ix(&sqr_ix(&x), self);
+            if x == n_minus_1 {
+                return true;
+            }
+        }
+        false
+    }
+
+    /// Strong Lucas probable-prime test with Selfridge's parameters, for
+    /// odd `self > 2` that is not a perfect square.
+    fn strong_lucas_probable_prime(&self) -> bool {
+        let n = self;
+        // Find D in 5, -7, 9, -11, ... with (D/n) = -1.
+        let mut d_abs = 5u64;
+        let mut d_neg = false;
+        let d = loop {
+            let d = ix {
+                sign: d_neg,
+                vals: vec![d_abs],
+            };
+            match d.jacobi(n) {
+                -1 => break d,
+                0 if n.vals != [d_abs] => return false,
+                _ => {}
+            }
+            d_abs += 2;
+            d_neg = !d_neg;
+        };
+        // P = 1, Q = (1 - D) / 4.
+        let q = ix {
+            sign: false,
+            vals: shr_mag(&sub_ix(&one(), &d).vals, 2),
+        };
+        let q = if d.sign { q } else { -q };
+
+        let n_plus_1 = add_ix(n, &one());
+        let s = trailing_zeros_mag(&n_plus_1.vals);
+        let k = shr_mag(&n_plus_1.vals, s);
+
+        let d_mod = rem_ix(&d, n);
+        let q_mod = rem_ix(&q, n);
+        let two = u64_to_ix(2);
+        let (mut u, mut v, mut qk) = (one(), one(), q_mod.clone());
+        for i in (0..bit_len(&k) - 1).rev() {
+            u = rem_ix(&mul_ix(&u, &v), n);
+            v = rem_ix(&sub_ix(&sqr_ix(&v), &mul_ix(&two, &qk)), n);
+            qk = rem_ix(&sqr_ix(&qk), n);
+            if k[i / 64] >> (i % 64) & 1 == 1 {
+                let next_u = half_mod(&rem_ix(&add_ix(&u, &v), n), n);
+                let next_v = half_mod(&rem_ix(&add_ix(&mul_ix(&d_mod, &u), &v), n), n);
+                u = next_u;
+                v = next_v;
+                qk = rem_ix(&mul_ix(&qk, &q_mod), n);
+            }
+        }
+        if u.vals.is_empty() || v.vals.is_empty() {
+            return true;
+        }
+        for _ in 1..s {
+            v = rem_ix(&sub_ix(&sqr_ix(&v), &mul_ix(&two, &qk)), n);
+            if v.vals.is_empty() {
+                return true;
+            }
+            qk = rem_ix(&sqr_ix(&qk), n);
+        }
+        false
+    }
+
+    /// Baillie–PSW: trial division, Miller–Rabin to base 2 and a strong
+    /// Lucas test. No composite is known to pass, and none exists below
+    /// 2^64, where the answer comes from `is_prime_u64` instead.
+    pub fn is_probable_prime(&self) -> bool {
+        if self.sign || self.vals.is_empty() {
+            return false;
+        }
+        if self.vals.len() == 1 {
+            return is_prime_u64(self.vals[0]);
+        }
+        match trial_division(self) {
+            Trial::Prime => return true,
+            Trial::Composite => return false,
+            Trial::Unknown => {}
+        }
+        if !self.strong_probable_prime(&u64_to_ix(2)) {
+            return false;
+        }
+        let root = self.isqrt();
+        if sqr_ix(&root) == *self {
+            return false;
+        }
+        self.strong_lucas_probable_prime()
+    }
+
+    /// The smallest prime strictly greater than `self`.
+    pub fn next_prime(&self) -> ix {
+        let two = u64_to_ix(2);
+        if self.sign || cmp_mag(&self.vals, &two.vals) < 0 {
+            return two;
+        }
+        let mut candidate = add_ix(self, &one());
+        if candidate.vals[0] & 1 == 0 {
+            candidate = add_ix(&candidate, &one());
+        }
+        while !candidate.is_probable_prime() {
+            candidate = add_ix(&candidate, &two);
+        }
+        candidate
+    }
+
+    /// Jacobi symbol `(self / n)` as -1, 0 or 1. Panics unless `n` is a
+    /// positive odd integer.
+    pub fn jacobi(&self, n: &ix) -> i32 {
+        assert!(
+            !n.sign && n.vals.first().is_some_and(|v| v & 1 == 1),
+            "Jacobi symbol needs a positive odd modulus"
+        );
+        let mut a = rem_ix(self, n);
+        let mut n = n.clone();
+        let mut t = 1;
+        while !a.vals.is_empty() {
+            let tz = trailing_zeros_mag(&a.vals);
+            a.vals = shr_mag(&a.vals, tz);
+            let n_mod_8 = n.vals[0] & 7;
+            if tz % 2 == 1 && (n_mod_8 == 3 || n_mod_8 == 5) {
+                t = -t;
+            }
+            std::mem::swap(&mut a, &mut n);
+            if a.vals[0] & 3 == 3 && n.vals[0] & 3 == 3 {
+                t = -t;
+            }
+            a = rem_ix(&a, &n);
+        }
+        if n.vals == [1] { t } else { 0 }
+    }
+}
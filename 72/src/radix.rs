@@ -0,0 +1,336 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::barrett::BarrettCtx;
+use crate::{add_mag, cmp_mag, div_rem_limb, ix, mul_mag, normalize_vals, sqr_mag, zero_ix};
+
+/// Below this many limbs, conversions peel one `u64` chunk of digits at a
+/// time; above it they split the number in half around a power of the radix.
+const DC_THRESHOLD: usize = 32;
+
+/// Error returned when parsing an `ix` from a string fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ParseIxError {
+    /// The input was empty.
+    Empty,
+    /// A character was not a valid digit in the requested radix.
+    InvalidDigit(char),
+    /// A `+` or `-` with no digits, or anywhere but the front.
+    StraySign,
+    /// A decimal exponent was too large to expand.
+    ExponentOutOfRange,
+}
+
+impl fmt::Display for ParseIxError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseIxError::Empty => f.write_str("cannot parse ix from empty string"),
+            ParseIxError::InvalidDigit(c) => write!(f, "invalid digit {:?} in ix literal", c),
+            ParseIxError::StraySign => f.write_str("misplaced sign in ix literal"),
+            ParseIxError::ExponentOutOfRange => {
+                f.write_str("exponent out of range in decimal literal")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ParseIxError {}
+
+/// Split an optionally signed run of decimal digits with at most one point,
+/// such as `"-12.375"` or `".5"`, into the integer formed by all its digits
+/// and the number of digits after the point: here `(-12375, 3)`.
+pub(crate) fn parse_point_digits(s: &str) -> Result<(ix, usize), ParseIxError> {
+    let (whole, frac) = s.split_once('.').unwrap_or((s, ""));
+    if frac.starts_with(['+', '-']) {
+        return Err(ParseIxError::StraySign);
+    }
+    let digits = format!("{}{}", whole, frac);
+    if digits.is_empty() {
+        return Err(ParseIxError::Empty);
+    }
+    if digits == "-" || digits == "+" {
+        return Err(ParseIxError::StraySign);
+    }
+    Ok((digits.parse()?, frac.len()))
+}
+
+fn check_radix(radix: u32) {
+    assert!(
+        (2..=36).contains(&radix),
+        "radix must be in 2..=36, got {}",
+        radix
+    );
+}
+
+/// The largest `k` with `radix^k` fitting in a limb, and that power.
+fn chunk_info(radix: u32) -> (usize, u64) {
+    let mut k = 0;
+    let mut base: u64 = 1;
+    while let Some(next) = base.checked_mul(radix as u64) {
+        base = next;
+        k += 1;
+    }
+    (k, base)
+}
+
+/// `vals = vals * m + a`, in place.
+fn mul_add_limb(vals: &mut Vec<u64>, m: u64, a: u64) {
+    let mut carry = a as u128;
+    for v in vals.iter_mut() {
+        let cur = (*v as u128) * (m as u128) + carry;
+        *v = cur as u64;
+        carry = cur >> 64;
+    }
+    if carry != 0 {
+        vals.push(carry as u64);
+    }
+}
+
+/// `powers[i] = base^(2^i)` for `i < count`.
+fn square_powers(base: u64, count: usize) -> Vec<Vec<u64>> {
+    let mut powers = vec![vec![base]];
+    while powers.len() < count {
+        let last = powers.last().unwrap();
+        let next = sqr_mag(last);
+        powers.push(next);
+    }
+    powers
+}
+
+fn parse_pow2(digits: &[u8], bits: u32) -> Vec<u64> {
+    let mut vals = vec![0u64; (digits.len() * bits as usize).div_ceil(64)];
+    for (i, &d) in digits.iter().rev().enumerate() {
+        let pos = i * bits as usize;
+        let (limb, off) = (pos / 64, pos % 64);
+        vals[limb] |= (d as u64) << off;
+        if off + bits as usize > 64 {
+            vals[limb + 1] |= (d as u64) >> (64 - off);
+        }
+    }
+    normalize_vals(&mut vals);
+    vals
+}
+
+fn parse_chunks(digits: &[u8], radix: u32, chunk: usize) -> Vec<u64> {
+    let mut vals = vec![];
+    let head = digits.len() % chunk;
+    let mut pieces = vec![&digits[..head]];
+    pieces.extend(digits[head..].chunks(chunk));
+    for piece in pieces {
+        if piece.is_empty() {
+            continue;
+        }
+        let mut v: u64 = 0;
+        let mut m: u64 = 1;
+        for &d in piece {
+            v = v * radix as u64 + d as u64;
+            m *= radix as u64;
+        }
+        mul_add_limb(&mut vals, m, v);
+    }
+    normalize_vals(&mut vals);
+    vals
+}
+
+/// Parse digit values (already checked against the radix) into a magnitude.
+fn parse_mag(digits: &[u8], radix: u32) -> Vec<u64> {
+    if radix.is_power_of_two() {
+        return parse_pow2(digits, radix.trailing_zeros());
+    }
+    let (chunk, base) = chunk_info(radix);
+    if digits.len() <= DC_THRESHOLD * chunk {
+        return parse_chunks(digits, radix, chunk);
+    }
+    let mut levels = 1;
+    while chunk << levels < digits.len() {
+        levels += 1;
+    }
+    let powers = square_powers(base, levels);
+    parse_dc(digits, radix, chunk, &powers, levels - 1)
+}
+
+/// The low part always holds exactly `chunk * 2^level` digits, so its
+/// weight is `powers[level]`.
+fn parse_dc(
+    digits: &[u8],
+    radix: u32,
+    chunk: usize,
+    powers: &[Vec<u64>],
+    mut level: usize,
+) -> Vec<u64> {
+    if digits.len() <= DC_THRESHOLD * chunk {
+        return parse_chunks(digits, radix, chunk);
+    }
+    while chunk << level >= digits.len() {
+        level -= 1;
+    }
+    let split = digits.len() - (chunk << level);
+    let hi = parse_dc(&digits[..split], radix, chunk, powers, level);
+    let lo = parse_dc(&digits[split..], radix, chunk, powers, level);
+    if hi.is_empty() {
+        return lo;
+    }
+    add_mag(&mul_mag(&hi, &powers[level]), &lo)
+}
+
+fn digit_char(d: u64) -> u8 {
+    if d < 10 {
+        b'0' + d as u8
+    } else {
+        b'a' + (d - 10) as u8
+    }
+}
+
+fn mag_to_pow2(vals: &[u64], bits: u32) -> Vec<u8> {
+    let total = crate::bit_len(vals).div_ceil(bits as usize);
+    let mask = (1u64 << bits) - 1;
+    let mut out = Vec::with_capacity(total);
+    for i in (0..total).rev() {
+        let pos = i * bits as usize;
+        let (limb, off) = (pos / 64, pos % 64);
+        let mut d = vals[limb] >> off;
+        if off + bits as usize > 64 && limb + 1 < vals.len() {
+            d |= vals[limb + 1] << (64 - off);
+        }
+        out.push(digit_char(d & mask));
+    }
+    out
+}
+
+/// Digits of `x` by repeated single-limb division, left-padded with zeros
+/// to `pad` digits.
+fn write_chunks(x: &[u64], radix: u32, chunk: usize, base: u64, pad: usize, out: &mut Vec<u8>) {
+    let mut pieces = vec![];
+    let mut cur = x.to_vec();
+    while !cur.is_empty() {
+        let (q, r) = div_rem_limb(&cur, base);
+        pieces.push(r);
+        cur = q;
+    }
+    let mut digits = vec![];
+    for (i, &p) in pieces.iter().enumerate() {
+        let mut p = p;
+        let last = i + 1 == pieces.len();
+        let mut n = 0;
+        while (last && p != 0) || (!last && n < chunk) {
+            digits.push(digit_char(p % radix as u64));
+            p /= radix as u64;
+            n += 1;
+        }
+    }
+    while digits.len() < pad {
+        digits.push(b'0');
+    }
+    out.extend(digits.iter().rev());
+}
+
+/// Write `x < powers[level + 1]` by splitting it around `powers[level]`.
+#[allow(clippy::too_many_arguments)]
+fn write_dc(
+    x: &[u64],
+    radix: u32,
+    chunk: usize,
+    base: u64,
+    powers: &[BarrettCtx],
+    level: usize,
+    pad: usize,
+    out: &mut Vec<u8>,
+) {
+    if x.len() <= DC_THRESHOLD || level == 0 {
+        write_chunks(x, radix, chunk, base, pad, out);
+        return;
+    }
+    let low_digits = chunk << level;
+    if cmp_mag(x, powers[level].modulus_mag()) < 0 {
+        write_dc(x, radix, chunk, base, powers, level - 1, pad, out);
+        return;
+    }
+    let (q, r) = powers[level].div_rem_mag(x);
+    let high_pad = pad.saturating_sub(low_digits);
+    write_dc(&q, radix, chunk, base, powers, level - 1, high_pad, out);
+    write_dc(&r, radix, chunk, base, powers, level - 1, low_digits, out);
+}
+
+/// Digits of a magnitude in the given radix, lowercase, without sign.
+pub(crate) fn mag_to_str_radix(vals: &[u64], radix: u32) -> String {
+    check_radix(radix);
+    if vals.is_empty() {
+        return "0".to_string();
+    }
+    let out = if radix.is_power_of_two() {
+        mag_to_pow2(vals, radix.trailing_zeros())
+    } else {
+        let (chunk, base) = chunk_info(radix);
+        let mut out = vec![];
+        if vals.len() <= DC_THRESHOLD {
+            write_chunks(vals, radix, chunk, base, 0, &mut out);
+        } else {
+            // Square until the last power exceeds x, so x < powers[top]^2
+            // and each half of every split fits the level below.
+            let mut powers = vec![vec![base]];
+            while cmp_mag(powers.last().unwrap(), vals) <= 0 {
+                let next = sqr_mag(powers.last().unwrap());
+                powers.push(next);
+            }
+            powers.pop();
+            let top = powers.len() - 1;
+            // Each power is a Barrett context, so a split costs two
+            // multiplications rather than a long division.
+            let powers: Vec<BarrettCtx> = powers.into_iter().map(BarrettCtx::from_mag).collect();
+            write_dc(vals, radix, chunk, base, &powers, top, 0, &mut out);
+        }
+        out
+    };
+    String::from_utf8(out).unwrap()
+}
+
+impl ix {
+    /// Parse an optionally signed string of digits in `radix` (2 to 36).
+    /// Letters may be either case; no `0x`-style prefix is accepted.
+    pub fn from_str_radix(s: &str, radix: u32) -> Result<ix, ParseIxError> {
+        check_radix(radix);
+        if s.is_empty() {
+            return Err(ParseIxError::Empty);
+        }
+        let (neg, body) = match s.as_bytes()[0] {
+            b'-' => (true, &s[1..]),
+            b'+' => (false, &s[1..]),
+            _ => (false, s),
+        };
+        if body.is_empty() {
+            return Err(ParseIxError::StraySign);
+        }
+        let mut digits = Vec::with_capacity(body.len());
+        for c in body.chars() {
+            match c.to_digit(radix) {
+                Some(d) => digits.push(d as u8),
+                None if c == '+' || c == '-' => return Err(ParseIxError::StraySign),
+                None => return Err(ParseIxError::InvalidDigit(c)),
+            }
+        }
+        let vals = parse_mag(&digits, radix);
+        if vals.is_empty() {
+            return Ok(zero_ix());
+        }
+        Ok(ix { sign: neg, vals })
+    }
+
+    /// Format in `radix` (2 to 36) with lowercase letters and a leading `-`
+    /// for negative values.
+    pub fn to_str_radix(&self, radix: u32) -> String {
+        let digits = mag_to_str_radix(&self.vals, radix);
+        if self.sign {
+            format!("-{}", digits)
+        } else {
+            digits
+        }
+    }
+}
+
+impl FromStr for ix {
+    type Err = ParseIxError;
+
+    fn from_str(s: &str) -> Result<ix, ParseIxError> {
+        ix::from_str_radix(s, 10)
+    }
+}
@@ -0,0 +1,295 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, AddAssign, Div, DivAssign, Mul, MulAssign, Neg, Sub, SubAssign};
+use std::str::FromStr;
+
+use crate::radix::{ParseIxError, parse_point_digits};
+use crate::rounding::{RoundingMode, div_round};
+use crate::{IxError, bit_len, checked_div_rem_mag, ix, shl_mag, u64_to_ix, zero_ix};
+
+/// Exact rational number. Always stored in lowest terms with a positive
+/// denominator, so equal values have equal representations.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Ratio {
+    numer: ix,
+    denom: ix,
+}
+
+impl Ratio {
+    /// `numer / denom` in lowest terms. Panics if `denom` is zero.
+    pub fn new(numer: ix, denom: ix) -> Ratio {
+        Ratio::checked_new(numer, denom).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// `numer / denom` in lowest terms, or `Err(DivisionByZero)`.
+    pub fn checked_new(numer: ix, denom: ix) -> Result<Ratio, IxError> {
+        if denom.vals.is_empty() {
+            return Err(IxError::DivisionByZero);
+        }
+        let g = numer.gcd(&denom);
+        let (mut numer, mut denom) = (&numer / &g, &denom / &g);
+        if denom.sign {
+            numer = -numer;
+            denom = -denom;
+        }
+        Ok(Ratio { numer, denom })
+    }
+
+    pub fn from_integer(n: ix) -> Ratio {
+        Ratio {
+            numer: n,
+            denom: u64_to_ix(1),
+        }
+    }
+
+    pub fn zero() -> Ratio {
+        Ratio::from_integer(zero_ix())
+    }
+
+    pub fn numer(&self) -> &ix {
+        &self.numer
+    }
+
+    /// Always positive.
+    pub fn denom(&self) -> &ix {
+        &self.denom
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.denom.vals == [1]
+    }
+
+    pub fn abs(&self) -> Ratio {
+        Ratio {
+            numer: ix {
+                sign: false,
+                vals: self.numer.vals.clone(),
+            },
+            denom: self.denom.clone(),
+        }
+    }
+
+    /// `1 / self`, or `Err(DivisionByZero)` for zero.
+    pub fn checked_recip(&self) -> Result<Ratio, IxError> {
+        Ratio::checked_new(self.denom.clone(), self.numer.clone())
+    }
+
+    pub fn recip(&self) -> Ratio {
+        self.checked_recip().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn checked_div(&self, other: &Ratio) -> Result<Ratio, IxError> {
+        Ratio::checked_new(&self.numer * &other.denom, &self.denom * &other.numer)
+    }
+
+    /// Round to an integer.
+    pub fn round(&self, mode: RoundingMode) -> ix {
+        div_round(&self.numer, &self.denom, mode)
+    }
+
+    /// The nearest `f64`, with ties to even, as for a correctly rounded
+    /// division. Overflows to infinity and underflows through the subnormals
+    /// to zero.
+    pub fn to_f64(&self) -> f64 {
+        let (n, d) = (&self.numer.vals, &self.denom.vals);
+        if n.is_empty() {
+            return 0.0;
+        }
+        // Scale so the integer quotient has 65 or 66 bits: enough for a
+        // 53-bit mantissa, a rounding bit and a guard bit, with the
+        // remainder acting as a sticky bit.
+        let s = 65 + bit_len(d) as i64 - bit_len(n) as i64;
+        let (q, r) = if s >= 0 {
+            checked_div_rem_mag(&shl_mag(n, s as usize), d)
+        } else {
+            checked_div_rem_mag(n, &shl_mag(d, s.unsigned_abs() as usize))
+        }
+        .unwrap();
+        let q = q[0] as u128 | (q.get(1).copied().unwrap_or(0) as u128) << 64;
+        let sticky = !r.is_empty();
+
+        // The value is q * 2^-s. Keep 53 bits, or fewer once the lowest kept
+        // bit would fall below 2^-1074.
+        let bl = 128 - q.leading_zeros() as i64;
+        let drop = std::cmp::max(bl - 53, s - 1074);
+        let mut mant = if drop > bl {
+            0
+        } else {
+            let drop = drop as u32;
+            let mant = q >> drop;
+            let rest = q & ((1u128 << drop) - 1);
+            let half = 1u128 << (drop - 1);
+            let up = rest > half || (rest == half && (sticky || mant & 1 == 1));
+            (mant + up as u128) as u64
+        };
+        let mut exp = drop - s;
+        if mant == 1 << 53 {
+            mant >>= 1;
+            exp += 1;
+        }
+
+        let bits = if mant < 1 << 52 {
+            // Subnormal (or zero): the exponent is pinned at -1074.
+            mant
+        } else {
+            let biased = exp + 52 + 1023;
+            if biased >= 0x7ff {
+                0x7ff << 52
+            } else {
+                (biased as u64) << 52 | (mant & ((1 << 52) - 1))
+            }
+        };
+        let magnitude = f64::from_bits(bits);
+        if self.numer.sign {
+            -magnitude
+        } else {
+            magnitude
+        }
+    }
+}
+
+impl Default for Ratio {
+    fn default() -> Ratio {
+        Ratio::zero()
+    }
+}
+
+impl From<ix> for Ratio {
+    fn from(n: ix) -> Ratio {
+        Ratio::from_integer(n)
+    }
+}
+
+impl From<i64> for Ratio {
+    fn from(n: i64) -> Ratio {
+        Ratio::from_integer(ix::from(n))
+    }
+}
+
+impl Ord for Ratio {
+    fn cmp(&self, other: &Ratio) -> Ordering {
+        (&self.numer * &other.denom).cmp(&(&other.numer * &self.denom))
+    }
+}
+
+impl PartialOrd for Ratio {
+    fn partial_cmp(&self, other: &Ratio) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+fn add_ratio(a: &Ratio, b: &Ratio) -> Ratio {
+    Ratio::new(
+        &a.numer * &b.denom + &b.numer * &a.denom,
+        &a.denom * &b.denom,
+    )
+}
+
+fn sub_ratio(a: &Ratio, b: &Ratio) -> Ratio {
+    Ratio::new(
+        &a.numer * &b.denom - &b.numer * &a.denom,
+        &a.denom * &b.denom,
+    )
+}
+
+fn mul_ratio(a: &Ratio, b: &Ratio) -> Ratio {
+    Ratio::new(&a.numer * &b.numer, &a.denom * &b.denom)
+}
+
+fn div_ratio(a: &Ratio, b: &Ratio) -> Ratio {
+    a.checked_div(b).unwrap_or_else(|e| panic!("{}", e))
+}
+
+macro_rules! ratio_binop {
+    ($Trait:ident, $method:ident, $AssignTrait:ident, $assign:ident, $func:ident) => {
+        impl $Trait<&Ratio> for &Ratio {
+            type Output = Ratio;
+            fn $method(self, rhs: &Ratio) -> Ratio {
+                $func(self, rhs)
+            }
+        }
+
+        impl $Trait<Ratio> for &Ratio {
+            type Output = Ratio;
+            fn $method(self, rhs: Ratio) -> Ratio {
+                $func(self, &rhs)
+            }
+        }
+
+        impl $Trait<&Ratio> for Ratio {
+            type Output = Ratio;
+            fn $method(self, rhs: &Ratio) -> Ratio {
+                $func(&self, rhs)
+            }
+        }
+
+        impl $Trait<Ratio> for Ratio {
+            type Output = Ratio;
+            fn $method(self, rhs: Ratio) -> Ratio {
+                $func(&self, &rhs)
+            }
+        }
+
+        impl $AssignTrait<&Ratio> for Ratio {
+            fn $assign(&mut self, rhs: &Ratio) {
+                *self = $func(self, rhs);
+            }
+        }
+
+        impl $AssignTrait<Ratio> for Ratio {
+            fn $assign(&mut self, rhs: Ratio) {
+                *self = $func(self, &rhs);
+            }
+        }
+    };
+}
+
+ratio_binop!(Add, add, AddAssign, add_assign, add_ratio);
+ratio_binop!(Sub, sub, SubAssign, sub_assign, sub_ratio);
+ratio_binop!(Mul, mul, MulAssign, mul_assign, mul_ratio);
+ratio_binop!(Div, div, DivAssign, div_assign, div_ratio);
+
+impl Neg for Ratio {
+    type Output = Ratio;
+    fn neg(self) -> Ratio {
+        Ratio {
+            numer: -self.numer,
+            denom: self.denom,
+        }
+    }
+}
+
+impl Neg for &Ratio {
+    type Output = Ratio;
+    fn neg(self) -> Ratio {
+        -self.clone()
+    }
+}
+
+impl fmt::Display for Ratio {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.numer)
+        } else {
+            write!(f, "{}/{}", self.numer, self.denom)
+        }
+    }
+}
+
+/// Parse `"a/b"`, an integer, or a decimal such as `"-12.375"` or `".5"`.
+/// A zero denominator is reported as `IxError::DivisionByZero`.
+impl FromStr for Ratio {
+    type Err = IxError;
+
+    fn from_str(s: &str) -> Result<Ratio, IxError> {
+        if let Some((n, d)) = s.split_once('/') {
+            let denom: ix = d.parse()?;
+            if d.starts_with(['+', '-']) {
+                return Err(ParseIxError::StraySign.into());
+            }
+            return Ratio::checked_new(n.parse()?, denom);
+        }
+        let (numer, frac_digits) = parse_point_digits(s)?;
+        Ratio::checked_new(numer, u64_to_ix(10).pow(frac_digits as u32))
+    }
+}
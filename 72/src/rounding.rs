@@ -0,0 +1,47 @@
+use crate::{cmp_mag, ix, u64_to_ix};
+
+/// How to round a value that falls between two integers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    /// Towards negative infinity.
+    Floor,
+    /// Towards positive infinity.
+    Ceil,
+    /// Towards zero, discarding the fraction.
+    Down,
+    /// Away from zero.
+    Up,
+    /// To the nearest integer, with ties going to the even one.
+    Nearest,
+    /// To the nearest integer, with ties going away from zero.
+    HalfUp,
+}
+
+/// `n / d` rounded to an integer by `mode`, for positive `d`.
+pub(crate) fn div_round(n: &ix, d: &ix, mode: RoundingMode) -> ix {
+    let (q, r) = n.checked_div_rem(d).unwrap();
+    if r.vals.is_empty() {
+        return q;
+    }
+    // `q` is the floor, so the quotient lies strictly between `q` and
+    // `q + 1`, and it is negative exactly when `q` is.
+    let negative = q.sign;
+    let half = cmp_mag(&(&r + &r).vals, &d.vals);
+    let up = match mode {
+        RoundingMode::Floor => false,
+        RoundingMode::Ceil => true,
+        RoundingMode::Down => negative,
+        RoundingMode::Up => !negative,
+        RoundingMode::Nearest => match half {
+            ..0 => false,
+            1.. => true,
+            0 => q.vals.first().is_some_and(|v| v & 1 == 1),
+        },
+        RoundingMode::HalfUp => match half {
+            ..0 => false,
+            1.. => true,
+            0 => !negative,
+        },
+    };
+    if up { q + u64_to_ix(1) } else { q }
+}
@@ -0,0 +1,408 @@
+use std::fmt;
+
+use crate::pem;
+use crate::prime::{RandomSource, random_prime};
+use crate::sha512::sha512;
+use crate::{bit_len, ix, u64_to_ix};
+
+// Textbook RSA with the PKCS#1 v2.2 (RFC 8017) encodings: RSAES-PKCS1-v1_5,
+// RSAES-OAEP and RSASSA-PKCS1-v1_5, all over SHA-512. It is written to be
+// read and to exercise `ix`, not to resist side channels: the modular
+// exponentiations are variable-time and decryption is not blinded.
+
+const HASH_LEN: usize = 64;
+
+/// DER `DigestInfo` header for a SHA-512 digest, from RFC 8017 section 9.2.
+const SHA512_DIGEST_INFO: [u8; 19] = [
+    0x30, 0x51, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x03, 0x05,
+    0x00, 0x04, 0x40,
+];
+
+const PUBLIC_LABEL: &str = "BIGNUM RSA PUBLIC KEY";
+const PRIVATE_LABEL: &str = "BIGNUM RSA PRIVATE KEY";
+
+/// Error returned by the RSA operations.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum RsaError {
+    /// The message does not fit the modulus with this padding.
+    MessageTooLong,
+    /// The ciphertext was malformed or did not decrypt under this key. The
+    /// cause is deliberately not reported.
+    Decryption,
+    /// The signature does not match the message.
+    BadSignature,
+    /// A key file could not be read or its numbers are inconsistent.
+    InvalidKey(&'static str),
+}
+
+impl fmt::Display for RsaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RsaError::MessageTooLong => f.write_str("message too long for RSA modulus"),
+            RsaError::Decryption => f.write_str("RSA decryption error"),
+            RsaError::BadSignature => f.write_str("RSA signature does not verify"),
+            RsaError::InvalidKey(why) => write!(f, "invalid RSA key: {}", why),
+        }
+    }
+}
+
+impl std::error::Error for RsaError {}
+
+/// An RSA public key `(n, e)`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RsaPublicKey {
+    n: ix,
+    e: ix,
+}
+
+/// An RSA private key, holding the CRT parameters alongside `d`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RsaPrivateKey {
+    public: RsaPublicKey,
+    d: ix,
+    p: ix,
+    q: ix,
+    /// `d mod (p - 1)`.
+    dp: ix,
+    /// `d mod (q - 1)`.
+    dq: ix,
+    /// `q^-1 mod p`.
+    qinv: ix,
+}
+
+fn one() -> ix {
+    u64_to_ix(1)
+}
+
+fn random_bytes(len: usize, rng: &mut impl RandomSource) -> Vec<u8> {
+    let mut out = Vec::with_capacity(len + 8);
+    while out.len() < len {
+        out.extend_from_slice(&rng.next_u64().to_le_bytes());
+    }
+    out.truncate(len);
+    out
+}
+
+/// MGF1 over SHA-512: `len` bytes of `H(seed || counter)` for counter 0, 1, ...
+fn mgf1(seed: &[u8], len: usize) -> Vec<u8> {
+    let mut out = Vec::with_capacity(len + HASH_LEN);
+    let mut input = seed.to_vec();
+    for counter in 0u32.. {
+        if out.len() >= len {
+            break;
+        }
+        input.truncate(seed.len());
+        input.extend_from_slice(&counter.to_be_bytes());
+        out.extend_from_slice(&sha512(&input));
+    }
+    out.truncate(len);
+    out
+}
+
+fn xor_in_place(dst: &mut [u8], mask: &[u8]) {
+    for (d, m) in dst.iter_mut().zip(mask) {
+        *d ^= m;
+    }
+}
+
+impl RsaPublicKey {
+    /// Panics unless `n` and `e` are greater than one.
+    pub fn new(n: ix, e: ix) -> RsaPublicKey {
+        assert!(
+            n > one() && e > one(),
+            "RSA modulus and exponent must exceed 1"
+        );
+        RsaPublicKey { n, e }
+    }
+
+    pub fn n(&self) -> &ix {
+        &self.n
+    }
+
+    pub fn e(&self) -> &ix {
+        &self.e
+    }
+
+    /// Length of the modulus in bytes, `k` in RFC 8017.
+    pub fn size(&self) -> usize {
+        bit_len(&self.n.vals).div_ceil(8)
+    }
+
+    /// `m^e mod n` for `0 <= m < n`.
+    pub fn encrypt_raw(&self, m: &ix) -> Result<ix, RsaError> {
+        if m.sign || *m >= self.n {
+            return Err(RsaError::MessageTooLong);
+        }
+        Ok(m.pow_mod(&self.e, &self.n))
+    }
+
+    /// Apply the public operation to a `k`-byte block.
+    fn public_block(&self, em: &[u8]) -> Result<Vec<u8>, RsaError> {
+        let c = self.encrypt_raw(&ix::from_bytes_be(em))?;
+        Ok(c.to_bytes_be_padded(self.size()).unwrap())
+    }
+
+    /// RSAES-PKCS1-v1_5 encryption: `0x00 0x02 PS 0x00 M` with at least
+    /// eight random non-zero padding bytes.
+    pub fn encrypt_pkcs1v15(
+        &self,
+        msg: &[u8],
+        rng: &mut impl RandomSource,
+    ) -> Result<Vec<u8>, RsaError> {
+        let k = self.size();
+        if msg.len() + 11 > k {
+            return Err(RsaError::MessageTooLong);
+        }
+        let mut em = vec![0x00, 0x02];
+        let ps_end = k - msg.len() - 1;
+        while em.len() < ps_end {
+            let want = ps_end - em.len();
+            em.extend(random_bytes(want, rng).into_iter().filter(|&b| b != 0));
+        }
+        em.push(0x00);
+        em.extend_from_slice(msg);
+        self.public_block(&em)
+    }
+
+    /// RSAES-OAEP encryption with SHA-512 and MGF1-SHA-512.
+    pub fn encrypt_oaep(
+        &self,
+        msg: &[u8],
+        label: &[u8],
+        rng: &mut impl RandomSource,
+    ) -> Result<Vec<u8>, RsaError> {
+        let k = self.size();
+        if k < 2 * HASH_LEN + 2 || msg.len() > k - 2 * HASH_LEN - 2 {
+            return Err(RsaError::MessageTooLong);
+        }
+        // DB = lHash || PS || 0x01 || M, then EM = 0x00 || maskedSeed || maskedDB.
+        let mut db = sha512(label).to_vec();
+        db.resize(k - msg.len() - HASH_LEN - 2, 0);
+        db.push(0x01);
+        db.extend_from_slice(msg);
+        let mut seed = random_bytes(HASH_LEN, rng);
+        let db_mask = mgf1(&seed, db.len());
+        xor_in_place(&mut db, &db_mask);
+        xor_in_place(&mut seed, &mgf1(&db, HASH_LEN));
+
+        let mut em = vec![0x00];
+        em.extend_from_slice(&seed);
+        em.extend_from_slice(&db);
+        self.public_block(&em)
+    }
+
+    /// RSASSA-PKCS1-v1_5 verification of a SHA-512 signature.
+    pub fn verify(&self, msg: &[u8], sig: &[u8]) -> Result<(), RsaError> {
+        let k = self.size();
+        if sig.len() != k {
+            return Err(RsaError::BadSignature);
+        }
+        let em = self.public_block(sig).map_err(|_| RsaError::BadSignature)?;
+        // Re-encode and compare, rather than parsing the DigestInfo.
+        match signature_encoding(msg, k) {
+            Ok(expected) if expected == em => Ok(()),
+            _ => Err(RsaError::BadSignature),
+        }
+    }
+
+    pub fn to_pem(&self) -> String {
+        let mut body = Vec::new();
+        self.n.write_varint(&mut body);
+        self.e.write_varint(&mut body);
+        pem::encode(PUBLIC_LABEL, &body)
+    }
+
+    /// Read a key written by `to_pem`. A private key file is accepted too,
+    /// yielding its public half.
+    pub fn from_pem(text: &str) -> Result<RsaPublicKey, RsaError> {
+        if let Some(body) = pem::decode(PUBLIC_LABEL, text) {
+            let [n, e] = read_numbers(&body)?;
+            if n <= one() || e <= one() {
+                return Err(RsaError::InvalidKey("modulus and exponent must exceed 1"));
+            }
+            return Ok(RsaPublicKey { n, e });
+        }
+        Ok(RsaPrivateKey::from_pem(text)?.public)
+    }
+}
+
+/// `EM = 0x00 0x01 FF..FF 0x00 DigestInfo(SHA-512(msg))`, `k` bytes long.
+fn signature_encoding(msg: &[u8], k: usize) -> Result<Vec<u8>, RsaError> {
+    let t_len = SHA512_DIGEST_INFO.len() + HASH_LEN;
+    if k < t_len + 11 {
+        return Err(RsaError::MessageTooLong);
+    }
+    let mut em = vec![0x00, 0x01];
+    em.resize(k - t_len - 1, 0xff);
+    em.push(0x00);
+    em.extend_from_slice(&SHA512_DIGEST_INFO);
+    em.extend_from_slice(&sha512(msg));
+    Ok(em)
+}
+
+/// Exactly `N` varint-encoded numbers filling `body`.
+fn read_numbers<const N: usize>(body: &[u8]) -> Result<[ix; N], RsaError> {
+    let mut rest = body;
+    let mut out = std::array::from_fn(|_| ix::default());
+    for slot in &mut out {
+        let (x, used) =
+            ix::from_varint(rest).map_err(|_| RsaError::InvalidKey("truncated key data"))?;
+        *slot = x;
+        rest = &rest[used..];
+    }
+    if !rest.is_empty() {
+        return Err(RsaError::InvalidKey("trailing key data"));
+    }
+    Ok(out)
+}
+
+impl RsaPrivateKey {
+    /// A fresh key with a modulus of exactly `bits` bits and `e = 65537`.
+    /// Panics if `bits` is below 512.
+    pub fn generate(bits: usize, rng: &mut impl RandomSource) -> RsaPrivateKey {
+        assert!(bits >= 512, "RSA modulus must be at least 512 bits");
+        let e = u64_to_ix(65537);
+        loop {
+            let p = random_prime(bits.div_ceil(2), rng);
+            let q = random_prime(bits / 2, rng);
+            if p == q || bit_len(&(&p * &q).vals) != bits {
+                continue;
+            }
+            if let Ok(key) = RsaPrivateKey::from_primes(p, q, e.clone()) {
+                return key;
+            }
+        }
+    }
+
+    /// Build a key from its primes, or fail if `e` is not invertible modulo
+    /// `lcm(p - 1, q - 1)`. The primes are not tested for primality.
+    pub fn from_primes(p: ix, q: ix, e: ix) -> Result<RsaPrivateKey, RsaError> {
+        if p <= one() || q <= one() || p == q {
+            return Err(RsaError::InvalidKey(
+                "p and q must be distinct and exceed 1",
+            ));
+        }
+        let (p1, q1) = (&p - one(), &q - one());
+        let d = e
+            .mod_inverse(&p1.lcm(&q1))
+            .ok_or(RsaError::InvalidKey("e is not invertible"))?;
+        let qinv = q
+            .mod_inverse(&p)
+            .ok_or(RsaError::InvalidKey("p and q are not coprime"))?;
+        Ok(RsaPrivateKey {
+            public: RsaPublicKey::new(&p * &q, e),
+            dp: d.rem_floor(&p1),
+            dq: d.rem_floor(&q1),
+            d,
+            p,
+            q,
+            qinv,
+        })
+    }
+
+    pub fn public_key(&self) -> &RsaPublicKey {
+        &self.public
+    }
+
+    pub fn d(&self) -> &ix {
+        &self.d
+    }
+
+    pub fn primes(&self) -> (&ix, &ix) {
+        (&self.p, &self.q)
+    }
+
+    /// `c^d mod n` for `0 <= c < n`, by Garner's CRT recombination: two
+    /// half-size exponentiations instead of one full-size one.
+    pub fn decrypt_raw(&self, c: &ix) -> Result<ix, RsaError> {
+        if c.sign || *c >= self.public.n {
+            return Err(RsaError::Decryption);
+        }
+        let m1 = c.pow_mod(&self.dp, &self.p);
+        let m2 = c.pow_mod(&self.dq, &self.q);
+        let h = (&self.qinv * (m1 - &m2)).rem_floor(&self.p);
+        Ok(m2 + h * &self.q)
+    }
+
+    /// Apply the private operation to a `k`-byte block.
+    fn private_block(&self, block: &[u8]) -> Result<Vec<u8>, RsaError> {
+        let k = self.public.size();
+        if block.len() != k {
+            return Err(RsaError::Decryption);
+        }
+        let m = self.decrypt_raw(&ix::from_bytes_be(block))?;
+        Ok(m.to_bytes_be_padded(k).unwrap())
+    }
+
+    /// RSAES-PKCS1-v1_5 decryption.
+    pub fn decrypt_pkcs1v15(&self, ct: &[u8]) -> Result<Vec<u8>, RsaError> {
+        let em = self.private_block(ct)?;
+        if em.len() < 11 || em[0] != 0x00 || em[1] != 0x02 {
+            return Err(RsaError::Decryption);
+        }
+        let sep = em[2..]
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or(RsaError::Decryption)?;
+        if sep < 8 {
+            return Err(RsaError::Decryption);
+        }
+        Ok(em[sep + 3..].to_vec())
+    }
+
+    /// RSAES-OAEP decryption with SHA-512 and MGF1-SHA-512.
+    pub fn decrypt_oaep(&self, ct: &[u8], label: &[u8]) -> Result<Vec<u8>, RsaError> {
+        let k = self.public.size();
+        if k < 2 * HASH_LEN + 2 {
+            return Err(RsaError::Decryption);
+        }
+        let em = self.private_block(ct)?;
+        let (mut seed, mut db) = (em[1..=HASH_LEN].to_vec(), em[HASH_LEN + 1..].to_vec());
+        xor_in_place(&mut seed, &mgf1(&db, HASH_LEN));
+        let db_mask = mgf1(&seed, db.len());
+        xor_in_place(&mut db, &db_mask);
+
+        // Check every field before failing, so all bad ciphertexts take
+        // the same path.
+        let label_ok = db[..HASH_LEN] == sha512(label);
+        let sep = db[HASH_LEN..].iter().position(|&b| b != 0);
+        let sep_ok = sep.is_some_and(|i| db[HASH_LEN + i] == 0x01);
+        if !(em[0] == 0x00 && label_ok && sep_ok) {
+            return Err(RsaError::Decryption);
+        }
+        Ok(db[HASH_LEN + sep.unwrap() + 1..].to_vec())
+    }
+
+    /// RSASSA-PKCS1-v1_5 signature over the SHA-512 digest of `msg`.
+    pub fn sign(&self, msg: &[u8]) -> Result<Vec<u8>, RsaError> {
+        let em = signature_encoding(msg, self.public.size())?;
+        self.private_block(&em)
+    }
+
+    pub fn to_pem(&self) -> String {
+        let mut body = Vec::new();
+        for x in [&self.public.n, &self.public.e, &self.d, &self.p, &self.q] {
+            x.write_varint(&mut body);
+        }
+        pem::encode(PRIVATE_LABEL, &body)
+    }
+
+    /// Read a key written by `to_pem`, recomputing the CRT parameters and
+    /// checking them against the stored modulus and exponents.
+    pub fn from_pem(text: &str) -> Result<RsaPrivateKey, RsaError> {
+        let body = pem::decode(PRIVATE_LABEL, text).ok_or(RsaError::InvalidKey("no key block"))?;
+        let [n, e, d, p, q] = read_numbers(&body)?;
+        let key = RsaPrivateKey::from_primes(p, q, e)?;
+        if key.public.n != n {
+            return Err(RsaError::InvalidKey("modulus is not p * q"));
+        }
+        // Any d with e * d ≡ 1 modulo both p - 1 and q - 1 is valid; keep
+        // the stored one if it is.
+        let (p1, q1) = (&key.p - one(), &key.q - one());
+        let ed = &key.public.e * &d;
+        if d.sign || ed.rem_floor(&p1) != one() || ed.rem_floor(&q1) != one() {
+            return Err(RsaError::InvalidKey("d does not match e"));
+        }
+        Ok(RsaPrivateKey { d, ..key })
+    }
+}
@@ -0,0 +1,119 @@
+use std::fmt;
+
+use serde::de::{self, Deserializer, SeqAccess, Unexpected, Visitor};
+use serde::ser::{SerializeTuple, Serializer};
+use serde::{Deserialize, Serialize};
+
+use crate::ix;
+
+// Human-readable formats get a decimal string, which survives JSON parsers
+// that would round a large bare number through `f64`. Binary formats get a
+// sign flag and the limbs as a length-prefixed sequence, least significant
+// first. Both read back only the canonical form: no leading zero limbs and
+// no negative zero.
+
+impl Serialize for ix {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            let mut tup = serializer.serialize_tuple(2)?;
+            tup.serialize_element(&self.sign)?;
+            tup.serialize_element(&self.vals)?;
+            tup.end()
+        }
+    }
+}
+
+/// Parse decimal, or hex with a `0x` prefix after any sign.
+fn parse_str<E: de::Error>(s: &str) -> Result<ix, E> {
+    let (neg, body) = match s.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, s),
+    };
+    let parsed = match body.strip_prefix("0x") {
+        Some(hex) if !hex.starts_with(['+', '-']) => {
+            ix::from_str_radix(hex, 16).map(|x| if neg { -x } else { x })
+        }
+        Some(_) => return Err(E::invalid_value(Unexpected::Str(s), &IxVisitor)),
+        None => s.parse(),
+    };
+    parsed.map_err(|_| E::invalid_value(Unexpected::Str(s), &IxVisitor))
+}
+
+struct IxVisitor;
+
+impl<'de> Visitor<'de> for IxVisitor {
+    type Value = ix;
+
+    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("an integer, a decimal or 0x-prefixed hex string, or (sign, limbs)")
+    }
+
+    fn visit_str<E: de::Error>(self, s: &str) -> Result<ix, E> {
+        parse_str(s)
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<ix, E> {
+        Ok(ix::from(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<ix, E> {
+        Ok(ix::from(v))
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<ix, E> {
+        Ok(ix::from(v))
+    }
+
+    fn visit_i128<E: de::Error>(self, v: i128) -> Result<ix, E> {
+        Ok(ix::from(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<ix, A::Error> {
+        let sign: bool = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let vals: Vec<u64> = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+        if vals.last() == Some(&0) {
+            return Err(de::Error::custom("ix limbs have a leading zero"));
+        }
+        if sign && vals.is_empty() {
+            return Err(de::Error::custom("ix is negative zero"));
+        }
+        Ok(ix { sign, vals })
+    }
+}
+
+impl<'de> Deserialize<'de> for ix {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<ix, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(IxVisitor)
+        } else {
+            deserializer.deserialize_tuple(2, IxVisitor)
+        }
+    }
+}
+
+/// Hex instead of decimal in human-readable formats, for use as
+/// `#[serde(with = "bignum::serde_hex")]`. Values look like `"0x1f"` or
+/// `"-0x1f"`. Binary formats are unchanged.
+pub mod hex {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::ix;
+
+    pub fn serialize<S: Serializer>(x: &ix, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(&format_args!("{:#x}", x))
+        } else {
+            serde::Serialize::serialize(x, serializer)
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ix, D::Error> {
+        ix::deserialize(deserializer)
+    }
+}
@@ -0,0 +1,202 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::ops::{Add, Mul, Sub};
+
+use crate::limb::{add_assign_limbs, mul_acc_limbs, sub_assign_limbs};
+use crate::{TryFromIxError, checked_div_rem_mag, ix, normalize_vals};
+
+/// Unsigned integer of exactly `LIMBS` 64-bit limbs, stored inline with the
+/// least significant limb first. Unlike `ix` it is `Copy`, never allocates
+/// for `+`, `-` and `*`, and keeps leading zero limbs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Uint<const LIMBS: usize> {
+    pub limbs: [u64; LIMBS],
+}
+
+pub type U256 = Uint<4>;
+pub type U512 = Uint<8>;
+
+impl<const LIMBS: usize> Uint<LIMBS> {
+    pub const BITS: usize = LIMBS * 64;
+    pub const ZERO: Self = Uint { limbs: [0; LIMBS] };
+    pub const MAX: Self = Uint {
+        limbs: [u64::MAX; LIMBS],
+    };
+    pub const ONE: Self = {
+        let mut limbs = [0; LIMBS];
+        limbs[0] = 1;
+        Uint { limbs }
+    };
+
+    pub const fn from_u64(v: u64) -> Self {
+        let mut limbs = [0; LIMBS];
+        limbs[0] = v;
+        Uint { limbs }
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.limbs.iter().all(|&v| v == 0)
+    }
+
+    /// The limbs without leading zeros, as the `ix` routines expect.
+    fn significant(&self) -> &[u64] {
+        let n = self.limbs.iter().rposition(|&v| v != 0).map_or(0, |i| i + 1);
+        &self.limbs[..n]
+    }
+
+    pub fn overflowing_add(&self, rhs: &Self) -> (Self, bool) {
+        let mut out = *self;
+        let carry = add_assign_limbs(&mut out.limbs, &rhs.limbs);
+        (out, carry != 0)
+    }
+
+    pub fn overflowing_sub(&self, rhs: &Self) -> (Self, bool) {
+        let mut out = *self;
+        let borrow = sub_assign_limbs(&mut out.limbs, &rhs.limbs);
+        (out, borrow != 0)
+    }
+
+    pub fn overflowing_mul(&self, rhs: &Self) -> (Self, bool) {
+        let mut out = Self::ZERO;
+        let overflow = mul_acc_limbs(&mut out.limbs, self.significant(), rhs.significant());
+        (out, overflow)
+    }
+
+    pub fn wrapping_add(&self, rhs: &Self) -> Self {
+        self.overflowing_add(rhs).0
+    }
+
+    pub fn wrapping_sub(&self, rhs: &Self) -> Self {
+        self.overflowing_sub(rhs).0
+    }
+
+    pub fn wrapping_mul(&self, rhs: &Self) -> Self {
+        self.overflowing_mul(rhs).0
+    }
+
+    pub fn checked_add(&self, rhs: &Self) -> Option<Self> {
+        match self.overflowing_add(rhs) {
+            (v, false) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn checked_sub(&self, rhs: &Self) -> Option<Self> {
+        match self.overflowing_sub(rhs) {
+            (v, false) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn checked_mul(&self, rhs: &Self) -> Option<Self> {
+        match self.overflowing_mul(rhs) {
+            (v, false) => Some(v),
+            _ => None,
+        }
+    }
+
+    /// Quotient and remainder, or `None` for a zero divisor.
+    pub fn checked_div_rem(&self, rhs: &Self) -> Option<(Self, Self)> {
+        let (q, r) = checked_div_rem_mag(self.significant(), rhs.significant()).ok()?;
+        Some((Self::from_mag(&q), Self::from_mag(&r)))
+    }
+
+    /// A magnitude known to fit in `LIMBS` limbs.
+    fn from_mag(vals: &[u64]) -> Self {
+        let mut out = Self::ZERO;
+        out.limbs[..vals.len()].copy_from_slice(vals);
+        out
+    }
+}
+
+impl<const LIMBS: usize> Default for Uint<LIMBS> {
+    fn default() -> Self {
+        Self::ZERO
+    }
+}
+
+impl<const LIMBS: usize> From<u64> for Uint<LIMBS> {
+    fn from(v: u64) -> Self {
+        Self::from_u64(v)
+    }
+}
+
+impl<const LIMBS: usize> Ord for Uint<LIMBS> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.limbs.iter().rev().cmp(other.limbs.iter().rev())
+    }
+}
+
+impl<const LIMBS: usize> PartialOrd for Uint<LIMBS> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Like the primitive integers in debug builds, the operators panic on
+// overflow; use the `wrapping_*` methods for modular arithmetic.
+macro_rules! uint_binop {
+    ($Trait:ident, $method:ident, $checked:ident, $msg:literal) => {
+        impl<const LIMBS: usize> $Trait for Uint<LIMBS> {
+            type Output = Self;
+            fn $method(self, rhs: Self) -> Self {
+                self.$checked(&rhs).expect($msg)
+            }
+        }
+
+        impl<const LIMBS: usize> $Trait<&Uint<LIMBS>> for &Uint<LIMBS> {
+            type Output = Uint<LIMBS>;
+            fn $method(self, rhs: &Uint<LIMBS>) -> Uint<LIMBS> {
+                self.$checked(rhs).expect($msg)
+            }
+        }
+    };
+}
+
+uint_binop!(Add, add, checked_add, "attempt to add with overflow");
+uint_binop!(Sub, sub, checked_sub, "attempt to subtract with overflow");
+uint_binop!(Mul, mul, checked_mul, "attempt to multiply with overflow");
+
+impl<const LIMBS: usize> From<&Uint<LIMBS>> for ix {
+    fn from(x: &Uint<LIMBS>) -> ix {
+        let mut vals = x.limbs.to_vec();
+        normalize_vals(&mut vals);
+        ix { sign: false, vals }
+    }
+}
+
+impl<const LIMBS: usize> From<Uint<LIMBS>> for ix {
+    fn from(x: Uint<LIMBS>) -> ix {
+        ix::from(&x)
+    }
+}
+
+/// Fails for negative values and values needing more than `LIMBS` limbs.
+impl<const LIMBS: usize> TryFrom<&ix> for Uint<LIMBS> {
+    type Error = TryFromIxError;
+    fn try_from(x: &ix) -> Result<Self, TryFromIxError> {
+        if x.sign || x.vals.len() > LIMBS {
+            return Err(TryFromIxError(()));
+        }
+        Ok(Self::from_mag(&x.vals))
+    }
+}
+
+impl<const LIMBS: usize> TryFrom<ix> for Uint<LIMBS> {
+    type Error = TryFromIxError;
+    fn try_from(x: ix) -> Result<Self, TryFromIxError> {
+        Self::try_from(&x)
+    }
+}
+
+impl<const LIMBS: usize> fmt::Display for Uint<LIMBS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&ix::from(self), f)
+    }
+}
+
+impl<const LIMBS: usize> fmt::LowerHex for Uint<LIMBS> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::LowerHex::fmt(&ix::from(self), f)
+    }
+}
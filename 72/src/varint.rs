@@ -0,0 +1,92 @@
+use std::fmt;
+
+use crate::{add_mag, bit_len, ix, normalize_vals, shl_mag, shr_mag, sub_mag};
+
+/// Error returned when decoding a varint fails.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum VarintError {
+    /// The input ended while a continuation bit was still set.
+    Truncated,
+    /// The encoding had redundant trailing zero groups, so it is not the
+    /// one `to_varint` would produce.
+    Overlong,
+}
+
+impl fmt::Display for VarintError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VarintError::Truncated => f.write_str("varint ended mid-value"),
+            VarintError::Overlong => f.write_str("varint is not minimally encoded"),
+        }
+    }
+}
+
+impl std::error::Error for VarintError {}
+
+// The format is LEB128 over the zigzag mapping 0, -1, 1, -2, ... ->
+// 0, 1, 2, 3, ...: seven bits per byte, least significant group first, with
+// the high bit set on every byte but the last. Small values of either sign
+// take one byte, and each value has exactly one encoding.
+impl ix {
+    /// Append the varint encoding of `self` to `out`.
+    pub fn write_varint(&self, out: &mut Vec<u8>) {
+        // 2|x| for x >= 0 and 2|x| - 1 for x < 0.
+        let doubled = shl_mag(&self.vals, 1);
+        let z = if self.sign {
+            sub_mag(&doubled, &[1])
+        } else {
+            doubled
+        };
+        let groups = std::cmp::max(bit_len(&z).div_ceil(7), 1);
+        for i in 0..groups {
+            let (limb, shift) = (7 * i / 64, 7 * i % 64);
+            let mut group = z.get(limb).map_or(0, |v| v >> shift);
+            if shift > 57 {
+                group |= z.get(limb + 1).map_or(0, |v| v << (64 - shift));
+            }
+            let more = if i + 1 < groups { 0x80 } else { 0 };
+            out.push(group as u8 & 0x7f | more);
+        }
+    }
+
+    /// The varint encoding of `self`.
+    pub fn to_varint(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write_varint(&mut out);
+        out
+    }
+
+    /// Decode one varint from the front of `bytes`, returning the value and
+    /// how many bytes it used. Anything after it is left for the caller.
+    pub fn from_varint(bytes: &[u8]) -> Result<(ix, usize), VarintError> {
+        let end = bytes
+            .iter()
+            .position(|&b| b & 0x80 == 0)
+            .ok_or(VarintError::Truncated)?;
+        if end > 0 && bytes[end] == 0 {
+            return Err(VarintError::Overlong);
+        }
+        let mut z = vec![0u64; (7 * (end + 1)).div_ceil(64)];
+        for (i, &b) in bytes[..=end].iter().enumerate() {
+            let (group, bit) = ((b & 0x7f) as u64, 7 * i);
+            z[bit / 64] |= group << (bit % 64);
+            if bit % 64 > 57 {
+                z[bit / 64 + 1] |= group >> (64 - bit % 64);
+            }
+        }
+        normalize_vals(&mut z);
+        let negative = z.first().is_some_and(|v| v & 1 == 1);
+        let vals = if negative {
+            shr_mag(&add_mag(&z, &[1]), 1)
+        } else {
+            shr_mag(&z, 1)
+        };
+        Ok((
+            ix {
+                sign: negative,
+                vals,
+            },
+            end + 1,
+        ))
+    }
+}
@@ -0,0 +1,71 @@
+use bignum::ix;
+
+fn n(v: i128) -> ix {
+    ix::from(v)
+}
+
+// Expected values were taken from CPython.
+#[test]
+fn bitwise_ops_match_python() {
+    let cases: &[(i128, i128)] = &[
+        (0, 0),
+        (-1, 0),
+        (-1, -1),
+        (12, -7),
+        (-12, 7),
+        (-(1 << 64), (1 << 64) - 1),
+        (-(1 << 70) + 3, -(1 << 63)),
+    ];
+    for &(a, b) in cases {
+        assert_eq!(n(a) & n(b), n(a & b), "{a} & {b}");
+        assert_eq!(n(a) | n(b), n(a | b), "{a} | {b}");
+        assert_eq!(n(a) ^ n(b), n(a ^ b), "{a} ^ {b}");
+        assert_eq!(!n(a), n(!a), "~{a}");
+    }
+    assert_eq!(n(-(1 << 64)) & n(u64::MAX as i128), n(0));
+}
+
+#[test]
+fn shifts_floor_like_python() {
+    assert_eq!(n(-1) >> 100, n(-1));
+    assert_eq!(n(-7) >> 1, n(-4));
+    assert_eq!(n(-8) >> 3, n(-1));
+    assert_eq!(n(-9) >> 3, n(-2));
+    assert_eq!(n(-(1 << 64)) >> 64, n(-1));
+    assert_eq!(n(-(1 << 64) - 1) >> 64, n(-2));
+    assert_eq!(n(5) >> 3, n(0));
+    assert_eq!(n(-5) << 70, n(-5 << 70));
+    let mut x = n(3);
+    x <<= 65;
+    x >>= 64;
+    assert_eq!(x, n(6));
+}
+
+#[test]
+fn bit_queries() {
+    assert_eq!(n(0).bit_len(), 0);
+    assert_eq!(n(-255).bit_len(), 8);
+    assert_eq!(n(1 << 64).bit_len(), 65);
+    assert_eq!(n(0).trailing_zeros(), None);
+    assert_eq!(n(-(1 << 67)).trailing_zeros(), Some(67));
+    assert_eq!(n(-255).count_ones(), 8);
+
+    // -6 is ...11010 in two's complement.
+    assert!(!n(-6).test_bit(0));
+    assert!(n(-6).test_bit(1));
+    assert!(!n(-6).test_bit(2));
+    assert!(n(-6).test_bit(500));
+
+    let mut x = n(-6);
+    x.set_bit(0, true);
+    assert_eq!(x, n(-5));
+    x.set_bit(200, false);
+    assert_eq!(x, n(-5) & !(n(1) << 200));
+    x.set_bit(200, true);
+    assert_eq!(x, n(-5));
+    let mut y = n(0);
+    y.set_bit(128, true);
+    assert_eq!(y, n(1) << 128);
+    y.set_bit(128, false);
+    assert_eq!(y, n(0));
+}
@@ -0,0 +1,63 @@
+use bignum::{BytesOverflowError, ix};
+
+#[test]
+fn unsigned_round_trip() {
+    let x = ix::from_str_radix("0102030405060708090a", 16).unwrap();
+    let be = x.to_bytes_be();
+    assert_eq!(be, [1, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+    let mut le = be.clone();
+    le.reverse();
+    assert_eq!(x.to_bytes_le(), le);
+    assert_eq!(ix::from_bytes_be(&be), x);
+    assert_eq!(ix::from_bytes_le(&le), x);
+    assert_eq!(ix::from(0u64).to_bytes_le(), [0]);
+    assert_eq!(ix::from_bytes_le(&[0, 0, 0]), ix::from(0u64));
+}
+
+#[test]
+fn padded_encoding() {
+    let x = ix::from(0x1234u64);
+    assert_eq!(x.to_bytes_le_padded(4), Ok(vec![0x34, 0x12, 0, 0]));
+    assert_eq!(x.to_bytes_be_padded(3), Ok(vec![0, 0x12, 0x34]));
+    assert_eq!(
+        x.to_bytes_le_padded(1),
+        Err(BytesOverflowError {
+            needed: 2,
+            width: 1
+        })
+    );
+    assert_eq!(ix::from(0u64).to_bytes_le_padded(0), Ok(vec![]));
+
+    // A 255-bit Ed25519 scalar always fits in 32 bytes.
+    let q = (ix::from(1u64) << 255) - ix::from(19u64);
+    let bytes = q.to_bytes_le_padded(32).unwrap();
+    assert_eq!(bytes[31], 0x7f);
+    assert_eq!(ix::from_bytes_le(&bytes), q);
+}
+
+#[test]
+fn signed_encoding_matches_twos_complement() {
+    let cases: &[(i128, &[u8])] = &[
+        (0, &[0]),
+        (1, &[1]),
+        (-1, &[0xff]),
+        (127, &[0x7f]),
+        (128, &[0x80, 0]),
+        (-128, &[0x80]),
+        (-129, &[0x7f, 0xff]),
+        (255, &[0xff, 0]),
+        (-256, &[0, 0xff]),
+    ];
+    for &(v, le) in cases {
+        let x = ix::from(v);
+        assert_eq!(x.to_signed_bytes_le(), le, "{v}");
+        assert_eq!(ix::from_signed_bytes_le(le), x, "{v}");
+        let mut be = le.to_vec();
+        be.reverse();
+        assert_eq!(x.to_signed_bytes_be(), be, "{v}");
+        assert_eq!(ix::from_signed_bytes_be(&be), x, "{v}");
+    }
+    assert_eq!(ix::from_signed_bytes_le(&[]), ix::from(0u64));
+    let big = -(ix::from(1u64) << 64);
+    assert_eq!(ix::from_signed_bytes_le(&big.to_signed_bytes_le()), big);
+}
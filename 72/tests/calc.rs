@@ -0,0 +1,88 @@
+use bignum::{CalcError, Calculator, IxError, ix};
+
+fn eval(src: &str) -> ix {
+    Calculator::new().eval_line(src).unwrap().unwrap()
+}
+
+fn n(v: i128) -> ix {
+    ix::from(v)
+}
+
+#[test]
+fn precedence_matches_python() {
+    assert_eq!(eval("1 + 2 * 3"), n(7));
+    assert_eq!(eval("(1 + 2) * 3"), n(9));
+    assert_eq!(eval("-2 ** 2"), n(-4));
+    assert_eq!(eval("2 ** 3 ** 2"), n(512));
+    assert_eq!(eval("1 << 2 + 1"), n(8));
+    assert_eq!(eval("6 & 3 | 8 ^ 1"), n(11));
+    assert_eq!(eval("~5 & 0xff"), n(250));
+    assert_eq!(eval("-7 // 2"), n(-4));
+    assert_eq!(eval("-7 / 2"), n(-4));
+    assert_eq!(eval("-7 % 2"), n(1));
+    assert_eq!(eval("7 % -2"), n(-1));
+    assert_eq!(eval("-1 >> 100"), n(-1));
+    assert_eq!(eval("0x_ff + 0b101 + 0o17 + 1_000"), n(255 + 5 + 15 + 1000));
+}
+
+#[test]
+fn variables_and_functions() {
+    let mut calc = Calculator::new();
+    assert_eq!(calc.eval_line("p = 2**127 - 1"), Ok(None));
+    assert_eq!(calc.eval_line("isprime(p)"), Ok(Some(n(1))));
+    assert_eq!(calc.eval_line("powmod(3, p - 1, p)"), Ok(Some(n(1))));
+    assert_eq!(calc.eval_line("_ + 1"), Ok(Some(n(2))));
+    assert_eq!(calc.eval_line("gcd(12, -18, 8)"), Ok(Some(n(2))));
+    assert_eq!(calc.eval_line("lcm(4, 6, 10)"), Ok(Some(n(60))));
+    assert_eq!(calc.eval_line("modinv(3, 7)"), Ok(Some(n(5))));
+    assert_eq!(calc.eval_line("root(-27, 3) + isqrt(99)"), Ok(Some(n(6))));
+    assert_eq!(calc.eval_line("max(1, -5, 3) - min(2, -4)"), Ok(Some(n(7))));
+    assert_eq!(calc.eval_line("   # comment only"), Ok(None));
+    assert_eq!(calc.get("p"), Some(&((n(1) << 127) - n(1))));
+}
+
+#[test]
+fn errors_are_reported_not_panicked() {
+    let mut calc = Calculator::new();
+    let mut err = |src: &str| calc.eval_line(src).unwrap_err();
+    assert_eq!(err("1 / 0"), CalcError::Arith(IxError::DivisionByZero));
+    assert_eq!(err("5 % (2 - 2)"), CalcError::Arith(IxError::DivisionByZero));
+    assert_eq!(err("powmod(2, 3, 0)"), CalcError::Arith(IxError::DivisionByZero));
+    assert_eq!(err("y + 1"), CalcError::UndefinedVariable("y".to_string()));
+    assert!(matches!(err("(1 + 2"), CalcError::Syntax(_)));
+    assert!(matches!(err("1 +"), CalcError::Syntax(_)));
+    assert!(matches!(err("12abc"), CalcError::Syntax(_)));
+    assert!(matches!(err("3 $ 4"), CalcError::Syntax(_)));
+    assert!(matches!(err("nope(1)"), CalcError::BadCall(_)));
+    assert!(matches!(err("gcd()"), CalcError::BadCall(_)));
+    assert!(matches!(err("2 ** -1"), CalcError::Domain(_)));
+    assert!(matches!(err("1 << -1"), CalcError::Domain(_)));
+    assert!(matches!(err("isqrt(-4)"), CalcError::Domain(_)));
+    assert!(matches!(err("modinv(2, 4)"), CalcError::Domain(_)));
+    assert!(matches!(err("jacobi(2, 4)"), CalcError::Domain(_)));
+}
+
+#[test]
+fn huge_shifts_and_powers_are_refused() {
+    let mut calc = Calculator::new();
+    let too_large = CalcError::Domain("result would exceed 16777216 bits".to_string());
+    for src in [
+        "1 << 10**12",
+        "1 << 2**64",
+        "10**10**10",
+        "2 ** 16777216",
+        "(-3) ** 20000000",
+        "(1 << 16777215) << 1",
+        "x = 3 ** 99999999",
+    ] {
+        assert_eq!(calc.eval_line(src), Err(too_large.clone()), "{}", src);
+    }
+    assert_eq!(calc.get("x"), None);
+    // Up to the budget is fine, and 0, 1 and -1 never grow.
+    assert_eq!(calc.eval_line("bitlen(1 << 16777215)"), Ok(Some(n(16777216))));
+    assert_eq!(calc.eval_line("bitlen(2 ** 16777215)"), Ok(Some(n(16777216))));
+    assert_eq!(calc.eval_line("0 << 10**12"), Ok(Some(n(0))));
+    assert_eq!(calc.eval_line("1 ** 10**20"), Ok(Some(n(1))));
+    assert_eq!(calc.eval_line("(-1) ** (10**20 + 1)"), Ok(Some(n(-1))));
+    assert_eq!(calc.eval_line("5 >> 10**30"), Ok(Some(n(0))));
+}
@@ -0,0 +1,174 @@
+use bignum::{IxError, ParseIxError, div_ix, h2i_ix, ix, rem_ix, sub_ix, try_h2i_ix};
+
+#[test]
+fn checked_division_reports_zero_divisor() {
+    let a = ix::from(7i64);
+    let zero = ix::from(0u64);
+    assert_eq!(a.checked_div(&zero), Err(IxError::DivisionByZero));
+    assert_eq!(a.checked_rem(&zero), Err(IxError::DivisionByZero));
+    assert_eq!(a.checked_div_rem(&zero), Err(IxError::DivisionByZero));
+    assert_eq!(zero.checked_div(&zero), Err(IxError::DivisionByZero));
+}
+
+#[test]
+fn checked_division_floors() {
+    for (a, b, q, r) in [
+        (7i64, 2i64, 3i64, 1i64),
+        (-7, 2, -4, 1),
+        (7, -2, -4, -1),
+        (-7, -2, 3, -1),
+        (-6, 3, -2, 0),
+        (0, -5, 0, 0),
+    ] {
+        let (a, b) = (ix::from(a), ix::from(b));
+        let expected = (ix::from(q), ix::from(r));
+        assert_eq!(a.checked_div_rem(&b), Ok(expected.clone()));
+        assert_eq!(div_ix(&a, &b), expected.0);
+        assert_eq!(rem_ix(&a, &b), expected.1);
+    }
+}
+
+#[test]
+fn rounding_conventions() {
+    // a, b, then (quotient, remainder) for trunc, floor and euclid.
+    for (a, b, trunc, floor, euclid) in [
+        (7i64, 2i64, (3, 1), (3, 1), (3, 1)),
+        (-7, 2, (-3, -1), (-4, 1), (-4, 1)),
+        (7, -2, (-3, 1), (-4, -1), (-3, 1)),
+        (-7, -2, (3, -1), (3, -1), (4, 1)),
+        (-6, 3, (-2, 0), (-2, 0), (-2, 0)),
+    ] {
+        let (a, b) = (ix::from(a), ix::from(b));
+        let pair = |(q, r): (i64, i64)| (ix::from(q), ix::from(r));
+        assert_eq!((a.div_trunc(&b), a.rem_trunc(&b)), pair(trunc));
+        assert_eq!((a.div_floor(&b), a.rem_floor(&b)), pair(floor));
+        assert_eq!((a.div_euclid(&b), a.rem_euclid(&b)), pair(euclid));
+    }
+    assert_eq!(
+        ix::from(1i64).checked_div_rem_euclid(&ix::from(0i64)),
+        Err(IxError::DivisionByZero)
+    );
+}
+
+#[test]
+#[should_panic(expected = "division by zero")]
+fn div_ix_still_panics() {
+    div_ix(&ix::from(1u64), &ix::from(0u64));
+}
+
+#[test]
+fn subtraction_never_yields_negative_zero() {
+    // sub_ix used to negate b without checking for zero, so 0 - 0 came
+    // out with its sign set and compared unequal to 0.
+    let zero = ix::from(0u64);
+    for x in [
+        ix::from(0u64),
+        ix::from(5u64),
+        ix::from(-5i64),
+        ix::from(1u128 << 64),
+        -(ix::from(1u128 << 100) * ix::from(3u64)),
+    ] {
+        let d = sub_ix(&x, &x);
+        assert!(!d.sign && d.vals.is_empty(), "{} - {}", x, x);
+        let d = &x - &x;
+        assert!(!d.sign, "{} - {}", x, x);
+        assert_eq!(d, zero);
+        let mut y = x.clone();
+        y -= &x;
+        assert!(!y.sign, "{} -= {}", x, x);
+    }
+    assert!(!sub_ix(&zero, &zero).sign);
+    assert!(!(-&zero).sign);
+}
+
+#[test]
+fn try_h2i_ix_rejects_bad_input() {
+    assert_eq!(try_h2i_ix(" -0xFF "), Ok(ix::from(-255i64)));
+    assert_eq!(try_h2i_ix("-0"), Ok(ix::from(0u64)));
+    assert_eq!(h2i_ix("10000000000000000"), ix::from(1u128 << 64));
+    assert_eq!(
+        try_h2i_ix("12g4"),
+        Err(IxError::Parse(ParseIxError::InvalidDigit('g')))
+    );
+    assert_eq!(
+        try_h2i_ix("1-2"),
+        Err(IxError::Parse(ParseIxError::StraySign))
+    );
+    assert_eq!(
+        try_h2i_ix("ä"),
+        Err(IxError::Parse(ParseIxError::InvalidDigit('ä')))
+    );
+}
+
+#[test]
+fn try_h2i_ix_rejects_stray_signs() {
+    for s in [
+        "-", " - ", "-0x", "-0X", "--5", "-+5", "+5", "0x-5", "0x+5", "-0x-5", "0x--",
+    ] {
+        assert_eq!(
+            try_h2i_ix(s),
+            Err(IxError::Parse(ParseIxError::StraySign)),
+            "{:?}",
+            s
+        );
+    }
+    // Digits are required.
+    for s in ["", "  ", "0x", "0X"] {
+        assert_eq!(
+            try_h2i_ix(s),
+            Err(IxError::Parse(ParseIxError::Empty)),
+            "{:?}",
+            s
+        );
+    }
+    assert_eq!(try_h2i_ix("-0x0"), Ok(ix::from(0u64)));
+    assert!(!try_h2i_ix("-0x0").unwrap().sign);
+    assert_eq!(try_h2i_ix("-0x5"), Ok(ix::from(-5i64)));
+}
+
+#[test]
+fn h2i_ix_keeps_its_lenient_rules() {
+    for (s, v) in [
+        ("", 0i128),
+        ("  ", 0),
+        ("-", 0),
+        ("0x", 0),
+        ("-0x", 0),
+        ("+5", 5),
+        ("-+5", -5),
+        ("0x+ff", 255),
+        ("-0", 0),
+        (" -0XfF ", -255),
+    ] {
+        assert_eq!(h2i_ix(s), ix::from(v), "{:?}", s);
+    }
+    assert!(!h2i_ix("-").sign);
+    // u64::from_str_radix takes a `+` at the start of every 16-digit chunk.
+    assert_eq!(
+        h2i_ix("1+000000000000001"),
+        (ix::from(1u64) << 64) + ix::from(1u64)
+    );
+    for s in ["--5", "0x-5", "-0x-5", "12g4", "ä"] {
+        assert!(
+            std::panic::catch_unwind(|| h2i_ix(s)).is_err(),
+            "{:?} should panic",
+            s
+        );
+    }
+}
+
+#[test]
+#[should_panic(expected = "bad hex input")]
+fn h2i_ix_panics_on_bad_digits() {
+    h2i_ix("0xzz");
+}
+
+#[test]
+fn try_parse_reports_ix_errors() {
+    assert_eq!(ix::try_parse("-123"), Ok(ix::from(-123i64)));
+    assert_eq!(ix::try_parse(""), Err(IxError::Parse(ParseIxError::Empty)));
+    assert_eq!(
+        ix::try_parse("12x"),
+        Err(IxError::Parse(ParseIxError::InvalidDigit('x')))
+    );
+}
@@ -0,0 +1,178 @@
+use std::hint::black_box;
+use std::time::Instant;
+
+use bignum::{Choice, CtMontgomeryCtx, U256, U512, Uint, ix};
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn uint<const LIMBS: usize>(&mut self) -> Uint<LIMBS> {
+        let mut limbs = [0u64; LIMBS];
+        for limb in limbs.iter_mut() {
+            *limb = match self.next() % 4 {
+                0 => 0,
+                1 => u64::MAX,
+                _ => self.next(),
+            };
+        }
+        Uint { limbs }
+    }
+}
+
+/// The curve25519 field prime, 2^255 - 19.
+fn p25519() -> U256 {
+    let mut p = U256::MAX;
+    p.limbs[3] >>= 1;
+    p.limbs[0] -= 18;
+    p
+}
+
+#[test]
+fn select_swap_and_compare() {
+    let (a, b) = (U256::from(3u64), U256::MAX);
+    assert_eq!(U256::ct_select(&a, &b, Choice::FALSE), a);
+    assert_eq!(U256::ct_select(&a, &b, Choice::TRUE), b);
+    let (mut x, mut y) = (a, b);
+    U256::ct_swap(&mut x, &mut y, Choice::from_bool(false));
+    assert_eq!((x, y), (a, b));
+    U256::ct_swap(&mut x, &mut y, Choice::from_bool(true));
+    assert_eq!((x, y), (b, a));
+
+    assert!(a.ct_lt(&b).to_bool());
+    assert!(!b.ct_lt(&a).to_bool());
+    assert!(!a.ct_lt(&a).to_bool());
+    assert!(b.ct_gt(&a).to_bool());
+    assert!(a.ct_eq(&a).to_bool());
+    assert!(!a.ct_eq(&b).to_bool());
+    assert!(U256::ZERO.ct_is_zero().to_bool());
+    assert!(!U256::ONE.ct_is_zero().to_bool());
+
+    assert_eq!(b.ct_add(&U256::ONE), (U256::ZERO, Choice::TRUE));
+    assert_eq!(U256::ZERO.ct_sub(&U256::ONE), (U256::MAX, Choice::TRUE));
+    assert_eq!(a.ct_sub(&U256::ONE), (U256::from(2u64), Choice::FALSE));
+}
+
+fn check_pow<const LIMBS: usize>(rng: &mut XorShift) {
+    let mut n = rng.uint::<LIMBS>();
+    n.limbs[0] |= 1;
+    if n == Uint::ONE {
+        return;
+    }
+    let ctx = CtMontgomeryCtx::new(&n).unwrap();
+    let (base, exp) = (rng.uint::<LIMBS>(), rng.uint::<LIMBS>());
+    let m = ix::from(&n);
+    let expected = ix::from(&base).pow_mod(&ix::from(&exp), &m);
+    assert_eq!(ix::from(ctx.pow(&base, &exp)), expected);
+
+    let a = Uint::try_from(ix::from(&base) % &m).unwrap();
+    let b = Uint::try_from(ix::from(&exp) % &m).unwrap();
+    let (ia, ib) = (ix::from(&a), ix::from(&b));
+    assert_eq!(ix::from(ctx.add(&a, &b)), (&ia + &ib) % &m);
+    assert_eq!(ix::from(ctx.sub(&a, &b)), (&ia - &ib) % &m);
+    let prod = ctx.from_mont(&ctx.mul(&ctx.to_mont(&a), &ctx.to_mont(&b)));
+    assert_eq!(ix::from(prod), (&ia * &ib) % &m);
+}
+
+#[test]
+fn montgomery_matches_pow_mod() {
+    let mut rng = XorShift(0x853c_49e6_748f_ea9b);
+    for _ in 0..200 {
+        check_pow::<1>(&mut rng);
+        check_pow::<4>(&mut rng);
+        check_pow::<8>(&mut rng);
+    }
+    assert!(CtMontgomeryCtx::new(&U256::from(10u64)).is_none());
+    assert!(CtMontgomeryCtx::new(&U256::ONE).is_none());
+
+    // Fermat: a^(p-1) = 1 mod p.
+    let p = p25519();
+    let ctx = CtMontgomeryCtx::new(&p).unwrap();
+    let e = p.wrapping_sub(&U256::ONE);
+    assert_eq!(ctx.pow(&U256::from(12345u64), &e), U256::ONE);
+    let q = U512::try_from(ix::from(&p)).unwrap();
+    assert!(CtMontgomeryCtx::new(&q).is_some());
+}
+
+/// Welch's t statistic between two timing samples.
+fn welch_t(a: &[f64], b: &[f64]) -> f64 {
+    let stats = |s: &[f64]| {
+        let n = s.len() as f64;
+        let mean = s.iter().sum::<f64>() / n;
+        let var = s.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1.0);
+        (mean, var, n)
+    };
+    let (ma, va, na) = stats(a);
+    let (mb, vb, nb) = stats(b);
+    (ma - mb) / (va / na + vb / nb).sqrt()
+}
+
+/// dudect-style fixed-vs-random test: time `op` on a fixed input and on
+/// random inputs in random order, drop the slowest tenth of each class as
+/// scheduling noise, and return |t|. Above 10 is a clear timing leak.
+fn leakage<T>(
+    fixed: T,
+    rng: &mut XorShift,
+    mut random: impl FnMut(&mut XorShift) -> T,
+    op: impl Fn(&T),
+) -> f64 {
+    const SAMPLES: usize = 20_000;
+    let mut times = [vec![], vec![]];
+    for _ in 0..SAMPLES {
+        let class = (rng.next() & 1) as usize;
+        let input = if class == 0 { None } else { Some(random(rng)) };
+        let input = input.as_ref().unwrap_or(&fixed);
+        let start = Instant::now();
+        op(black_box(input));
+        times[class].push(start.elapsed().as_nanos() as f64);
+    }
+    for t in times.iter_mut() {
+        t.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        t.truncate(t.len() * 9 / 10);
+    }
+    welch_t(&times[0], &times[1]).abs()
+}
+
+// Timing tests depend on the machine, so they only run on request:
+//     cargo test --release --test ct -- --ignored
+
+#[test]
+#[ignore]
+fn ct_pow_timing_is_independent_of_secrets() {
+    let mut rng = XorShift(0x9e37_79b9_7f4a_7c15);
+    let p = p25519();
+    let ctx = CtMontgomeryCtx::new(&p).unwrap();
+    let t = leakage(
+        (U256::ONE, U256::ZERO),
+        &mut rng,
+        |rng| (rng.uint::<4>(), rng.uint::<4>()),
+        |(base, exp)| {
+            black_box(ctx.pow(base, exp));
+        },
+    );
+    assert!(t < 10.0, "constant-time pow leaks: |t| = {:.1}", t);
+}
+
+/// The same harness must flag the variable-time `ix::pow_mod`, or it is not
+/// measuring anything.
+#[test]
+#[ignore]
+fn harness_detects_variable_time_pow_mod() {
+    let mut rng = XorShift(0x9e37_79b9_7f4a_7c15);
+    let p = ix::from(&p25519());
+    let t = leakage(
+        (ix::from(1u64), ix::from(0u64)),
+        &mut rng,
+        |rng| (ix::from(&rng.uint::<4>()), ix::from(&rng.uint::<4>())),
+        |(base, exp)| {
+            black_box(base.pow_mod(exp, &p));
+        },
+    );
+    assert!(t > 10.0, "leak not detected: |t| = {:.1}", t);
+}
@@ -0,0 +1,213 @@
+use bignum::{Decimal, IxError, ParseIxError, RoundingMode, ix};
+
+fn d(s: &str) -> Decimal {
+    s.parse().unwrap()
+}
+
+#[test]
+fn parse_and_format() {
+    for s in [
+        "0",
+        "1",
+        "-1",
+        "0.5",
+        "-0.05",
+        "12.3400",
+        "1000000000000000000000.000000000000000000001",
+    ] {
+        assert_eq!(d(s).to_string(), s);
+    }
+    assert_eq!(d(".5").to_string(), "0.5");
+    assert_eq!(d("+7.").to_string(), "7");
+    assert_eq!(d("6.02e23").to_string(), "602000000000000000000000");
+    assert_eq!(d("1.5E-3").to_string(), "0.0015");
+    assert_eq!(d("-25e-1").scale(), 1);
+    assert_eq!(d("12.3400").mantissa(), &ix::from(123400i64));
+    assert_eq!(format!("{:>8}", d("-1.5")), "    -1.5");
+
+    assert_eq!("".parse::<Decimal>().unwrap_err(), ParseIxError::Empty);
+    assert_eq!("-".parse::<Decimal>().unwrap_err(), ParseIxError::StraySign);
+    assert_eq!(
+        "1.-5".parse::<Decimal>().unwrap_err(),
+        ParseIxError::StraySign
+    );
+    assert_eq!(
+        "1.2.3".parse::<Decimal>().unwrap_err(),
+        ParseIxError::InvalidDigit('.')
+    );
+    assert!("1e".parse::<Decimal>().is_err());
+    assert!("1e99999999999".parse::<Decimal>().is_err());
+}
+
+#[test]
+fn huge_exponents_are_refused() {
+    // These would otherwise build 10^4000000000 or a scale past u32.
+    for s in [
+        "1e4000000000",
+        "1e-4000000000",
+        "-2.5E+1000001",
+        "0e1000001",
+        "1e99999999999999999999",
+    ] {
+        assert_eq!(
+            s.parse::<Decimal>().unwrap_err(),
+            ParseIxError::ExponentOutOfRange,
+            "{}",
+            s
+        );
+    }
+    assert_eq!(
+        ParseIxError::ExponentOutOfRange.to_string(),
+        "exponent out of range in decimal literal"
+    );
+    assert_eq!(d("1e1000").to_string(), format!("1{}", "0".repeat(1000)));
+    assert_eq!(d("1e-1000000").scale(), 1000000);
+    assert_eq!(
+        d("3e100000").mantissa(),
+        &(ix::from(10u64).pow(100000) * ix::from(3u64))
+    );
+    assert_eq!(d("-0.5e+2").to_string(), "-50");
+}
+
+#[test]
+fn malformed_exponents() {
+    for (s, err) in [
+        ("1e", ParseIxError::InvalidDigit('e')),
+        ("1E", ParseIxError::InvalidDigit('E')),
+        ("1e+", ParseIxError::StraySign),
+        ("1e-+5", ParseIxError::StraySign),
+        ("1e5x", ParseIxError::InvalidDigit('x')),
+        ("1e 5", ParseIxError::InvalidDigit(' ')),
+        ("1e5e5", ParseIxError::InvalidDigit('e')),
+        ("e5", ParseIxError::Empty),
+        ("-e5", ParseIxError::StraySign),
+    ] {
+        assert_eq!(s.parse::<Decimal>().unwrap_err(), err, "{:?}", s);
+    }
+}
+
+#[test]
+fn exact_arithmetic() {
+    let mut sum = Decimal::zero();
+    for _ in 0..10 {
+        sum += d("0.1");
+    }
+    assert_eq!(sum, Decimal::from(1));
+    assert_eq!(sum.to_string(), "1.0");
+
+    assert_eq!((d("1.25") + d("-3.5")).to_string(), "-2.25");
+    assert_eq!((d("10") - d("0.001")).to_string(), "9.999");
+    assert_eq!((d("-1.5") * d("0.25")).to_string(), "-0.375");
+    assert_eq!((-d("2.50")).to_string(), "-2.50");
+    assert_eq!(d("1.50"), d("1.5"));
+    assert!(d("-0.01") < d("0") && d("0.1") > d("0.09999"));
+    assert_eq!(d("1.2300").normalized().to_string(), "1.23");
+    assert_eq!(d("0.000").normalized().to_string(), "0");
+    assert_eq!(d("100").normalized().to_string(), "100");
+}
+
+#[test]
+fn rounding_modes() {
+    use RoundingMode::*;
+    // value, then Floor, Ceil, Down, Up, Nearest, HalfUp to an integer.
+    let cases = [
+        ("5.5", [5, 6, 5, 6, 6, 6]),
+        ("2.5", [2, 3, 2, 3, 2, 3]),
+        ("1.6", [1, 2, 1, 2, 2, 2]),
+        ("1.1", [1, 2, 1, 2, 1, 1]),
+        ("1.0", [1, 1, 1, 1, 1, 1]),
+        ("-1.0", [-1, -1, -1, -1, -1, -1]),
+        ("-1.1", [-2, -1, -1, -2, -1, -1]),
+        ("-1.6", [-2, -1, -1, -2, -2, -2]),
+        ("-2.5", [-3, -2, -2, -3, -2, -3]),
+        ("-5.5", [-6, -5, -5, -6, -6, -6]),
+    ];
+    for (s, expected) in cases {
+        for (mode, want) in [Floor, Ceil, Down, Up, Nearest, HalfUp]
+            .into_iter()
+            .zip(expected)
+        {
+            assert_eq!(
+                d(s).with_scale(0, mode),
+                Decimal::from(want),
+                "{s} {mode:?}"
+            );
+        }
+    }
+    assert_eq!(d("2.345").with_scale(2, HalfUp).to_string(), "2.35");
+    assert_eq!(d("2.345").with_scale(2, Nearest).to_string(), "2.34");
+    assert_eq!(d("2.3").with_scale(3, Floor).to_string(), "2.300");
+}
+
+#[test]
+fn division_to_precision() {
+    use RoundingMode::*;
+    assert_eq!(d("1").div(&d("3"), 5, Nearest).to_string(), "0.33333");
+    assert_eq!(d("2").div(&d("3"), 5, Nearest).to_string(), "0.66667");
+    assert_eq!(d("2").div(&d("3"), 5, Down).to_string(), "0.66666");
+    assert_eq!(d("-2").div(&d("3"), 5, Floor).to_string(), "-0.66667");
+    assert_eq!(d("1").div(&d("-8"), 2, HalfUp).to_string(), "-0.13");
+    assert_eq!(d("1").div(&d("-8"), 2, Nearest).to_string(), "-0.12");
+    assert_eq!(d("0.0001").div(&d("0.02"), 1, Ceil).to_string(), "0.1");
+    assert_eq!(
+        d("123.456").div(&d("0.001"), 0, Nearest).to_string(),
+        "123456"
+    );
+    assert_eq!(d("22").div(&d("7"), 0, Down).to_string(), "3");
+    assert_eq!(
+        d("1").checked_div(&d("0.00"), 3, Nearest),
+        Err(IxError::DivisionByZero)
+    );
+    // Division and multiplication agree up to the dropped digits.
+    let q = d("987654321.123456789").div(&d("-0.0375"), 40, Floor);
+    let back = &q * d("-0.0375");
+    assert!(back >= d("987654321.123456789"));
+    assert!(back - d("987654321.123456789") < d("1e-40"));
+}
+
+#[test]
+fn square_roots() {
+    use RoundingMode::*;
+    assert_eq!(
+        d("2").sqrt(60, Nearest).to_string(),
+        "1.414213562373095048801688724209698078569671875376948073176680"
+    );
+    assert_eq!(d("2").sqrt(3, Floor).to_string(), "1.414");
+    assert_eq!(d("2").sqrt(3, Ceil).to_string(), "1.415");
+    assert_eq!(d("1.44").sqrt(1, Ceil).to_string(), "1.2");
+    assert_eq!(d("0.0001").sqrt(4, Nearest).to_string(), "0.0100");
+    assert_eq!(d("0.001").sqrt(1, Nearest).to_string(), "0.0");
+    // A tie: sqrt(0.25) is exactly 0.5.
+    assert_eq!(d("0.25").sqrt(0, Nearest).to_string(), "0");
+    assert_eq!(d("2.25").sqrt(0, Nearest).to_string(), "2");
+    assert_eq!(d("0.25").sqrt(0, HalfUp).to_string(), "1");
+    for s in ["0", "1", "3", "10.5", "123456.789"] {
+        let x = d(s);
+        let r = x.sqrt(30, Floor);
+        let next = &r + d("1e-30");
+        assert!(&r * &r <= x && &next * &next > x, "{s}");
+    }
+}
+
+#[test]
+#[should_panic(expected = "negative")]
+fn sqrt_of_negative_panics() {
+    d("-1").sqrt(2, RoundingMode::Nearest);
+}
+
+#[test]
+fn pi_digits() {
+    const PI_200: &str = "3.\
+        14159265358979323846264338327950288419716939937510\
+        58209749445923078164062862089986280348253421170679\
+        82148086513282306647093844609550582231725359408128\
+        48111745028410270193852110555964462294895493038196";
+    assert_eq!(Decimal::pi(200).to_string(), PI_200);
+    assert_eq!(Decimal::pi(0).to_string(), "3");
+    assert_eq!(Decimal::pi(4).to_string(), "3.1416");
+    for k in [1u32, 10, 31, 99] {
+        let expected = d(PI_200).with_scale(k, RoundingMode::Nearest);
+        assert_eq!(Decimal::pi(k), expected, "{k}");
+    }
+    assert_eq!(Decimal::pi(1000).scale(), 1000);
+}
@@ -0,0 +1,528 @@
+//! Differential tests of `ix` against `num-bigint`.
+//!
+//! Each operation runs on random operands biased towards zero, signs,
+//! single limbs and limb boundaries. A mismatch or panic is shrunk to a
+//! small failing case, which is appended to `tests/corpus/differential.txt`
+//! and replayed on every later run.
+//!
+//! `BIGNUM_DIFF_CASES` sets the number of random cases per operation.
+
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::panic::{self, AssertUnwindSafe};
+
+use bignum::{ix, sqr_ix};
+use num_bigint::{BigInt, BigUint, Sign};
+use num_integer::Integer;
+use num_traits::{Euclid, One, Signed, ToPrimitive, Zero};
+
+const CORPUS: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/corpus/differential.txt");
+const DEFAULT_CASES: usize = 300;
+
+/// Conversions go through limbs so they do not depend on the radix or byte
+/// code under test.
+fn to_big(x: &ix) -> BigInt {
+    let digits: Vec<u32> = x
+        .vals
+        .iter()
+        .flat_map(|&v| [v as u32, (v >> 32) as u32])
+        .collect();
+    let sign = if x.sign { Sign::Minus } else { Sign::Plus };
+    BigInt::from_biguint(sign, BigUint::new(digits))
+}
+
+fn from_big(x: &BigInt) -> ix {
+    let mut vals = x.magnitude().to_u64_digits();
+    while vals.last() == Some(&0) {
+        vals.pop();
+    }
+    ix {
+        sign: x.is_negative(),
+        vals,
+    }
+}
+
+fn show(x: &ix) -> String {
+    to_big(x).to_string()
+}
+
+/// A small non-negative parameter derived from an operand.
+fn small(x: &BigInt, modulus: u64) -> u64 {
+    (x.magnitude() % modulus).to_u64().unwrap()
+}
+
+struct Op {
+    name: &'static str,
+    arity: usize,
+    ours: fn(&[ix]) -> String,
+    reference: fn(&[BigInt]) -> String,
+}
+
+const ERR: &str = "error";
+
+fn ops() -> Vec<Op> {
+    vec![
+        Op {
+            name: "add",
+            arity: 2,
+            ours: |a| show(&(&a[0] + &a[1])),
+            reference: |a| (&a[0] + &a[1]).to_string(),
+        },
+        Op {
+            name: "sub",
+            arity: 2,
+            ours: |a| show(&(&a[0] - &a[1])),
+            reference: |a| (&a[0] - &a[1]).to_string(),
+        },
+        Op {
+            name: "mul",
+            arity: 2,
+            ours: |a| show(&(&a[0] * &a[1])),
+            reference: |a| (&a[0] * &a[1]).to_string(),
+        },
+        Op {
+            name: "sqr",
+            arity: 1,
+            ours: |a| show(&sqr_ix(&a[0])),
+            reference: |a| (&a[0] * &a[0]).to_string(),
+        },
+        Op {
+            name: "div_rem",
+            arity: 2,
+            ours: |a| match a[0].checked_div_rem(&a[1]) {
+                Ok((q, r)) => format!("{} {}", show(&q), show(&r)),
+                Err(_) => ERR.to_string(),
+            },
+            reference: |a| {
+                if a[1].is_zero() {
+                    return ERR.to_string();
+                }
+                let (q, r) = a[0].div_mod_floor(&a[1]);
+                format!("{} {}", q, r)
+            },
+        },
+        Op {
+            name: "div_rem_trunc",
+            arity: 2,
+            ours: |a| match a[0].checked_div_rem_trunc(&a[1]) {
+                Ok((q, r)) => format!("{} {}", show(&q), show(&r)),
+                Err(_) => ERR.to_string(),
+            },
+            reference: |a| {
+                if a[1].is_zero() {
+                    return ERR.to_string();
+                }
+                let (q, r) = a[0].div_rem(&a[1]);
+                format!("{} {}", q, r)
+            },
+        },
+        Op {
+            name: "div_rem_euclid",
+            arity: 2,
+            ours: |a| match a[0].checked_div_rem_euclid(&a[1]) {
+                Ok((q, r)) => format!("{} {}", show(&q), show(&r)),
+                Err(_) => ERR.to_string(),
+            },
+            reference: |a| {
+                if a[1].is_zero() {
+                    return ERR.to_string();
+                }
+                format!("{} {}", a[0].div_euclid(&a[1]), a[0].rem_euclid(&a[1]))
+            },
+        },
+        Op {
+            name: "neg",
+            arity: 1,
+            ours: |a| show(&-&a[0]),
+            reference: |a| (-&a[0]).to_string(),
+        },
+        Op {
+            name: "cmp",
+            arity: 2,
+            ours: |a| format!("{:?}", a[0].cmp(&a[1])),
+            reference: |a| format!("{:?}", a[0].cmp(&a[1])),
+        },
+        Op {
+            name: "and",
+            arity: 2,
+            ours: |a| show(&(&a[0] & &a[1])),
+            reference: |a| (&a[0] & &a[1]).to_string(),
+        },
+        Op {
+            name: "or",
+            arity: 2,
+            ours: |a| show(&(&a[0] | &a[1])),
+            reference: |a| (&a[0] | &a[1]).to_string(),
+        },
+        Op {
+            name: "xor",
+            arity: 2,
+            ours: |a| show(&(&a[0] ^ &a[1])),
+            reference: |a| (&a[0] ^ &a[1]).to_string(),
+        },
+        Op {
+            name: "not",
+            arity: 1,
+            ours: |a| show(&!&a[0]),
+            reference: |a| (!&a[0]).to_string(),
+        },
+        Op {
+            name: "shl",
+            arity: 2,
+            ours: |a| show(&(&a[0] << small(&to_big(&a[1]), 300) as usize)),
+            reference: |a| (&a[0] << small(&a[1], 300)).to_string(),
+        },
+        Op {
+            name: "shr",
+            arity: 2,
+            ours: |a| show(&(&a[0] >> small(&to_big(&a[1]), 300) as usize)),
+            reference: |a| (&a[0] >> small(&a[1], 300)).to_string(),
+        },
+        Op {
+            name: "bits",
+            arity: 2,
+            ours: |a| {
+                let k = small(&to_big(&a[1]), 300) as usize;
+                format!(
+                    "{} {:?} {} {}",
+                    a[0].bit_len(),
+                    a[0].trailing_zeros(),
+                    a[0].count_ones(),
+                    a[0].test_bit(k)
+                )
+            },
+            reference: |a| {
+                let k = small(&a[1], 300);
+                format!(
+                    "{} {:?} {} {}",
+                    a[0].bits(),
+                    a[0].trailing_zeros().map(|v| v as usize),
+                    a[0].magnitude().count_ones(),
+                    a[0].bit(k)
+                )
+            },
+        },
+        Op {
+            name: "pow",
+            arity: 2,
+            ours: |a| show(&a[0].pow(small(&to_big(&a[1]), 8) as u32)),
+            reference: |a| num_traits::pow(a[0].clone(), small(&a[1], 8) as usize).to_string(),
+        },
+        Op {
+            name: "gcd_lcm",
+            arity: 2,
+            ours: |a| format!("{} {}", show(&a[0].gcd(&a[1])), show(&a[0].lcm(&a[1]))),
+            reference: |a| format!("{} {}", a[0].gcd(&a[1]), a[0].lcm(&a[1])),
+        },
+        Op {
+            name: "isqrt",
+            arity: 1,
+            ours: |a| show(&abs_ix(&a[0]).isqrt()),
+            reference: |a| a[0].abs().sqrt().to_string(),
+        },
+        Op {
+            name: "pow_mod",
+            arity: 3,
+            ours: |a| {
+                if a[2].vals.is_empty() {
+                    return ERR.to_string();
+                }
+                show(&a[0].pow_mod(&abs_ix(&a[1]), &a[2]))
+            },
+            reference: |a| {
+                if a[2].is_zero() {
+                    return ERR.to_string();
+                }
+                let m = a[2].abs();
+                a[0].mod_floor(&m)
+                    .modpow(&a[1].abs(), &m)
+                    .mod_floor(&a[2])
+                    .to_string()
+            },
+        },
+        Op {
+            name: "mod_inverse",
+            arity: 2,
+            ours: |a| {
+                if a[1].vals.is_empty() {
+                    return ERR.to_string();
+                }
+                a[0].mod_inverse(&a[1])
+                    .map_or(ERR.to_string(), |x| show(&x))
+            },
+            reference: |a| {
+                if a[1].is_zero() {
+                    return ERR.to_string();
+                }
+                let e = a[0].extended_gcd(&a[1]);
+                if !e.gcd.is_one() {
+                    return ERR.to_string();
+                }
+                e.x.mod_floor(&a[1]).to_string()
+            },
+        },
+        Op {
+            name: "radix",
+            arity: 2,
+            ours: |a| {
+                let radix = 2 + small(&to_big(&a[1]), 35) as u32;
+                let s = a[0].to_str_radix(radix);
+                let back = ix::from_str_radix(&s, radix).map(|x| show(&x));
+                format!("{} {:?}", s, back)
+            },
+            reference: |a| {
+                let radix = 2 + small(&a[1], 35) as u32;
+                format!(
+                    "{} {:?}",
+                    a[0].to_str_radix(radix),
+                    Ok::<_, ()>(a[0].to_string())
+                )
+            },
+        },
+        Op {
+            name: "bytes",
+            arity: 1,
+            ours: |a| {
+                let le = a[0].to_signed_bytes_le();
+                let be = a[0].to_bytes_be();
+                format!("{:?} {:?} {}", le, be, show(&ix::from_signed_bytes_le(&le)))
+            },
+            reference: |a| {
+                format!(
+                    "{:?} {:?} {}",
+                    a[0].to_signed_bytes_le(),
+                    a[0].to_bytes_be().1,
+                    a[0]
+                )
+            },
+        },
+    ]
+}
+
+fn abs_ix(x: &ix) -> ix {
+    ix {
+        sign: false,
+        vals: x.vals.clone(),
+    }
+}
+
+/// `None` if both sides agree; otherwise what each side produced.
+fn mismatch(op: &Op, args: &[ix]) -> Option<(String, String)> {
+    let big: Vec<BigInt> = args.iter().map(to_big).collect();
+    let ours = panic::catch_unwind(AssertUnwindSafe(|| (op.ours)(args)))
+        .unwrap_or_else(|_| "panic".to_string());
+    let reference = (op.reference)(&big);
+    (ours != reference).then_some((ours, reference))
+}
+
+fn shrink_candidates(x: &ix) -> Vec<ix> {
+    let mut out = vec![ix::from(0u64), ix::from(1u64), ix::from(-1i64)];
+    if x.sign {
+        out.push(abs_ix(x));
+    }
+    let n = x.vals.len();
+    if n > 1 {
+        out.push(ix {
+            sign: x.sign,
+            vals: x.vals[..n - 1].to_vec(),
+        });
+        out.push(ix {
+            sign: x.sign,
+            vals: x.vals[1..].to_vec(),
+        });
+    }
+    for i in 0..n.saturating_sub(1) {
+        for simpler in [0, 1, u64::MAX] {
+            if x.vals[i] != simpler {
+                let mut vals = x.vals.clone();
+                vals[i] = simpler;
+                out.push(ix { sign: x.sign, vals });
+            }
+        }
+    }
+    if !x.vals.is_empty() {
+        out.push(x >> 1);
+    }
+    out.retain(|c| c != x);
+    out
+}
+
+/// Greedily replace operands with simpler ones while the case still fails.
+fn shrink(op: &Op, mut args: Vec<ix>) -> Vec<ix> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    'outer: loop {
+        for i in 0..args.len() {
+            for candidate in shrink_candidates(&args[i]) {
+                let mut trial = args.clone();
+                trial[i] = candidate;
+                if mismatch(op, &trial).is_some() {
+                    args = trial;
+                    continue 'outer;
+                }
+            }
+        }
+        break;
+    }
+    panic::set_hook(hook);
+    args
+}
+
+fn corpus_line(op: &Op, args: &[ix]) -> String {
+    let mut line = op.name.to_string();
+    for a in args {
+        line.push(' ');
+        line.push_str(&show(a));
+    }
+    line
+}
+
+fn record(line: &str) {
+    let existing = fs::read_to_string(CORPUS).unwrap_or_default();
+    if existing.lines().any(|l| l == line) {
+        return;
+    }
+    if let Ok(mut f) = OpenOptions::new().create(true).append(true).open(CORPUS) {
+        let _ = writeln!(f, "{}", line);
+    }
+}
+
+fn fail(op: &Op, args: Vec<ix>) -> ! {
+    let args = shrink(op, args);
+    let line = corpus_line(op, &args);
+    record(&line);
+    let (ours, reference) = mismatch(op, &args).unwrap_or_default();
+    panic!(
+        "{} disagrees with num-bigint\n  case: {}\n  ours: {}\n  num-bigint: {}\n(saved to the regression corpus)",
+        op.name, line, ours, reference
+    );
+}
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn below(&mut self, n: u64) -> u64 {
+        self.next() % n
+    }
+
+    fn limbs(&mut self, n: usize) -> Vec<u64> {
+        (0..n)
+            .map(|_| match self.below(4) {
+                0 => 0,
+                1 => u64::MAX,
+                _ => self.next(),
+            })
+            .collect()
+    }
+
+    /// Operands biased towards the cases bignum code gets wrong: zero,
+    /// one, single limbs, values at and around limb boundaries, powers of
+    /// two, runs of all-zero or all-one limbs, and multi-limb values on
+    /// both sides of the Karatsuba threshold.
+    fn operand(&mut self) -> ix {
+        let mut vals = match self.below(9) {
+            0 => vec![],
+            1 => vec![1],
+            2 => vec![self.next()],
+            3 => {
+                let k = 1 + self.below(3) as usize;
+                let mut v = vec![0; k];
+                v.push(1);
+                match self.below(4) {
+                    0 => vec![u64::MAX; k],
+                    1 => v,
+                    2 => {
+                        v[0] = 1;
+                        v
+                    }
+                    _ => {
+                        let mut v = vec![0; k];
+                        v[k - 1] = 1 << 63;
+                        v
+                    }
+                }
+            }
+            4 => {
+                let bit = self.below(300) as usize;
+                let p = ix::from(1u64) << bit;
+                let p = match self.below(3) {
+                    0 => p - ix::from(1u64),
+                    1 => p + ix::from(1u64),
+                    _ => p,
+                };
+                p.vals
+            }
+            5 => {
+                let n = 1 + self.below(6) as usize;
+                self.limbs(n)
+            }
+            6 | 7 => {
+                let n = 1 + self.below(8) as usize;
+                (0..n).map(|_| self.next()).collect()
+            }
+            _ => {
+                let n = 30 + self.below(40) as usize;
+                self.limbs(n)
+            }
+        };
+        while vals.last() == Some(&0) {
+            vals.pop();
+        }
+        let sign = !vals.is_empty() && self.below(2) == 0;
+        ix { sign, vals }
+    }
+}
+
+fn parse_corpus_value(s: &str) -> ix {
+    from_big(&s.parse::<BigInt>().expect("corpus values are decimal"))
+}
+
+#[test]
+fn regression_corpus() {
+    let ops = ops();
+    let corpus = fs::read_to_string(CORPUS).expect("missing regression corpus");
+    for line in corpus.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split_whitespace();
+        let name = fields.next().unwrap();
+        let op = ops
+            .iter()
+            .find(|op| op.name == name)
+            .unwrap_or_else(|| panic!("unknown operation in corpus: {}", line));
+        let args: Vec<ix> = fields.map(parse_corpus_value).collect();
+        assert_eq!(args.len(), op.arity, "wrong operand count: {}", line);
+        if let Some((ours, reference)) = mismatch(op, &args) {
+            panic!(
+                "corpus case fails\n  case: {}\n  ours: {}\n  num-bigint: {}",
+                line, ours, reference
+            );
+        }
+    }
+}
+
+#[test]
+fn random_operands_match_num_bigint() {
+    let cases = std::env::var("BIGNUM_DIFF_CASES")
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(DEFAULT_CASES);
+    let mut rng = XorShift(0xd1b5_4a32_d192_ed03);
+    for op in &ops() {
+        for _ in 0..cases {
+            let args: Vec<ix> = (0..op.arity).map(|_| rng.operand()).collect();
+            if mismatch(op, &args).is_some() {
+                fail(op, args);
+            }
+        }
+    }
+}
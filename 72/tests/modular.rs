@@ -0,0 +1,227 @@
+use bignum::ix;
+
+fn n(v: i128) -> ix {
+    ix::from(v)
+}
+
+fn big(s: &str) -> ix {
+    s.parse().unwrap()
+}
+
+#[test]
+fn gcd_and_lcm_table() {
+    for (a, b, g, l) in [
+        (0, 0, 0, 0),
+        (0, -5, 5, 0),
+        (7, 0, 7, 0),
+        (-12, 18, 6, 36),
+        (12, -18, 6, 36),
+        (-4, -6, 2, 12),
+        (17, 5, 1, 85),
+        (1, 1, 1, 1),
+        (-1, 1, 1, 1),
+    ] {
+        assert_eq!(n(a).gcd(&n(b)), n(g), "gcd({}, {})", a, b);
+        assert_eq!(n(a).lcm(&n(b)), n(l), "lcm({}, {})", a, b);
+    }
+    let x = n(3) << 60;
+    assert_eq!((n(1) << 100).gcd(&x), n(1) << 60);
+    assert_eq!((n(1) << 100).lcm(&x), n(3) << 100);
+}
+
+#[test]
+fn egcd_satisfies_bezout() {
+    for (a, b) in [
+        (0, 0),
+        (0, 5),
+        (5, 0),
+        (-7, 0),
+        (0, -7),
+        (240, 46),
+        (-240, 46),
+        (240, -46),
+        (-240, -46),
+        (1, -1),
+        (17, 17),
+    ] {
+        let (a, b) = (n(a), n(b));
+        let (g, x, y) = a.egcd(&b);
+        assert_eq!(g, a.gcd(&b), "egcd({}, {})", a, b);
+        assert_eq!(&a * &x + &b * &y, g, "egcd({}, {})", a, b);
+    }
+    let a = (n(1) << 127) - n(1);
+    let b = n(3).pow(80);
+    let (g, x, y) = a.egcd(&b);
+    assert_eq!(g, n(1));
+    assert_eq!(&a * &x + &b * &y, g);
+}
+
+#[test]
+fn mod_inverse_table() {
+    for (a, m, inv) in [
+        (3, 7, Some(5)),
+        (-3, 7, Some(2)),
+        (10, 7, Some(5)),
+        // The result takes the sign of the modulus, as rem_ix does.
+        (3, -7, Some(-2)),
+        (1, 2, Some(1)),
+        (4, 1, Some(0)),
+        (2, 4, None),
+        (6, 9, None),
+        (0, 5, None),
+        (5, 0, None),
+        (0, 1, Some(0)),
+    ] {
+        assert_eq!(
+            n(a).mod_inverse(&n(m)),
+            inv.map(n),
+            "inverse of {} mod {}",
+            a,
+            m
+        );
+    }
+    let p = (n(1) << 127) - n(1);
+    let a = big("123456789012345678901234567890");
+    let inv = a.mod_inverse(&p).unwrap();
+    assert_eq!((&a * &inv) % &p, n(1));
+    assert_eq!((n(1) << 64).mod_inverse(&(n(1) << 65)), None);
+}
+
+#[test]
+fn isqrt_around_perfect_squares() {
+    let roots = [
+        n(1),
+        n(2),
+        n(3),
+        n(u32::MAX as i128),
+        n(u64::MAX as i128),
+        (n(1) << 100) + n(12345),
+        n(7).pow(150),
+    ];
+    for k in roots {
+        let sq = &k * &k;
+        assert_eq!((&sq - n(1)).isqrt(), &k - n(1), "isqrt({}^2 - 1)", k);
+        assert_eq!(sq.isqrt(), k, "isqrt({}^2)", k);
+        assert_eq!((&sq + n(1)).isqrt(), k, "isqrt({}^2 + 1)", k);
+    }
+    assert_eq!(n(0).isqrt(), n(0));
+    assert_eq!(n(1).isqrt(), n(1));
+    assert_eq!(n(3).isqrt(), n(1));
+}
+
+#[test]
+fn nth_root_around_perfect_powers() {
+    for e in [3u32, 4, 5, 7, 64] {
+        for k in [n(2), n(3), n(10), n(1000003), (n(1) << 70) + n(1)] {
+            let p = k.pow(e);
+            assert_eq!((&p - n(1)).nth_root(e), &k - n(1), "({}^{} - 1)", k, e);
+            assert_eq!(p.nth_root(e), k, "{}^{}", k, e);
+            assert_eq!((&p + n(1)).nth_root(e), k, "{}^{} + 1", k, e);
+        }
+    }
+    assert_eq!((n(1) << 64).nth_root(64), n(2));
+    assert_eq!(((n(1) << 64) - n(1)).nth_root(64), n(1));
+    assert_eq!(n(12345).nth_root(1), n(12345));
+    assert_eq!(n(-12345).nth_root(1), n(-12345));
+    assert_eq!(n(0).nth_root(9), n(0));
+    assert_eq!(n(5).nth_root(100), n(1));
+}
+
+#[test]
+fn odd_roots_of_negatives_floor() {
+    for (x, e, r) in [
+        (-8, 3, -2),
+        (-7, 3, -2),
+        (-9, 3, -3),
+        (-1, 3, -1),
+        (-2, 5, -2),
+        (-32, 5, -2),
+        (-33, 5, -3),
+    ] {
+        assert_eq!(n(x).nth_root(e), n(r), "root {} of {}", e, x);
+    }
+    let k = (n(1) << 80) + n(7);
+    let c = -k.pow(3);
+    assert_eq!(c.nth_root(3), -&k);
+    assert_eq!((&c - n(1)).nth_root(3), -&k - n(1));
+    assert_eq!((&c + n(1)).nth_root(3), -&k);
+}
+
+#[test]
+#[should_panic(expected = "even root of a negative number")]
+fn isqrt_of_negative_panics() {
+    n(-4).isqrt();
+}
+
+#[test]
+#[should_panic(expected = "zeroth root is undefined")]
+fn zeroth_root_panics() {
+    n(4).nth_root(0);
+}
+
+#[test]
+fn pow_mod_even_and_signed_moduli() {
+    for (b, e, m, r) in [
+        (3, 100, 1000, 1),
+        (2, 10, 1000, 24),
+        (-2, 3, 10, 2),
+        (3, 3, -10, -3),
+        (-3, 3, -10, -7),
+        (7, 0, 10, 1),
+        (0, 0, 10, 1),
+        (0, 5, 10, 0),
+        (5, 3, 2, 1),
+        (6, 200, 4096, 0),
+    ] {
+        assert_eq!(n(b).pow_mod(&n(e), &n(m)), n(r), "{}^{} mod {}", b, e, m);
+    }
+    // Modulus 1 (or -1) always gives zero, even for exponent zero.
+    for m in [1, -1] {
+        assert_eq!(n(5).pow_mod(&n(0), &n(m)), n(0));
+        assert_eq!(n(-5).pow_mod(&n(7), &n(m)), n(0));
+        assert_eq!(n(0).pow_mod(&n(0), &n(m)), n(0));
+    }
+    // Negative exponents invert first.
+    assert_eq!(n(3).pow_mod(&n(-1), &n(10)), n(7));
+    assert_eq!(n(3).pow_mod(&n(-2), &n(10)), n(9));
+}
+
+#[test]
+fn pow_mod_matches_pow_then_reduce() {
+    let moduli = [
+        n(1) << 64,
+        (n(1) << 64) + n(2),
+        n(10).pow(40),
+        -(n(2) * n(3).pow(50)),
+        (n(1) << 300) - n(6),
+        n(997),
+    ];
+    let bases = [n(2), n(-3), (n(1) << 70) + n(9), -n(10).pow(30)];
+    for m in &moduli {
+        for b in &bases {
+            for e in [1u32, 2, 17, 64, 129] {
+                let expected = b.pow(e) % m;
+                assert_eq!(
+                    b.pow_mod(&n(e as i128), m),
+                    expected,
+                    "{}^{} mod {}",
+                    b,
+                    e,
+                    m
+                );
+            }
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "pow_mod with zero modulus")]
+fn pow_mod_zero_modulus_panics() {
+    n(2).pow_mod(&n(3), &n(0));
+}
+
+#[test]
+#[should_panic(expected = "non-invertible")]
+fn pow_mod_negative_exponent_needs_inverse() {
+    n(2).pow_mod(&n(-1), &n(10));
+}
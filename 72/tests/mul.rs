@@ -0,0 +1,107 @@
+use bignum::{
+    KARATSUBA_THRESHOLD, TOOM3_THRESHOLD, ix, mul_ix, mul_mag, mul_schoolbook, sqr_ix, sqr_mag,
+    sqr_schoolbook,
+};
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// `n` limbs biased towards all-zero and all-one limbs, which is where
+    /// carry and borrow bugs hide.
+    fn limbs(&mut self, n: usize) -> Vec<u64> {
+        let mut v: Vec<u64> = (0..n)
+            .map(|_| match self.next() % 4 {
+                0 => 0,
+                1 => u64::MAX,
+                _ => self.next(),
+            })
+            .collect();
+        if let Some(top) = v.last_mut() {
+            *top |= 1;
+        }
+        v
+    }
+}
+
+fn sizes_around(t: usize) -> Vec<usize> {
+    vec![t - 1, t, t + 1, t + 7, 2 * t - 1, 2 * t + 3]
+}
+
+#[test]
+fn karatsuba_matches_schoolbook() {
+    let mut rng = XorShift(0x243f_6a88_85a3_08d3);
+    for n in sizes_around(KARATSUBA_THRESHOLD) {
+        for m in [n, n - 1, n / 2 + 1, KARATSUBA_THRESHOLD] {
+            for _ in 0..20 {
+                let a = rng.limbs(n);
+                let b = rng.limbs(m);
+                assert_eq!(mul_mag(&a, &b), mul_schoolbook(&a, &b), "{}x{}", n, m);
+            }
+        }
+    }
+}
+
+#[test]
+fn toom3_matches_schoolbook() {
+    let mut rng = XorShift(0x1319_8a2e_0370_7344);
+    for n in sizes_around(TOOM3_THRESHOLD) {
+        for m in [n, n - 2, n / 2 + 1, TOOM3_THRESHOLD] {
+            for _ in 0..3 {
+                let a = rng.limbs(n);
+                let b = rng.limbs(m);
+                assert_eq!(mul_mag(&a, &b), mul_schoolbook(&a, &b), "{}x{}", n, m);
+            }
+        }
+    }
+}
+
+#[test]
+fn unbalanced_operands_match_schoolbook() {
+    let mut rng = XorShift(0xa409_3822_299f_31d0);
+    for &(n, m) in &[(1000, 40), (700, 200), (97, 33), (5, 900)] {
+        let a = rng.limbs(n);
+        let b = rng.limbs(m);
+        assert_eq!(mul_mag(&a, &b), mul_schoolbook(&a, &b), "{}x{}", n, m);
+    }
+}
+
+#[test]
+fn squaring_matches_schoolbook() {
+    let mut rng = XorShift(0x0820_efa9_8ec4_e6c8);
+    let mut sizes = vec![0, 1, 2, 3, 17];
+    sizes.extend(sizes_around(KARATSUBA_THRESHOLD));
+    sizes.extend(sizes_around(TOOM3_THRESHOLD));
+    for n in sizes {
+        let a = rng.limbs(n);
+        let expected = mul_schoolbook(&a, &a);
+        assert_eq!(sqr_schoolbook(&a), expected, "{}", n);
+        assert_eq!(sqr_mag(&a), expected, "{}", n);
+    }
+}
+
+#[test]
+fn mul_ix_signs_and_squares() {
+    let mut rng = XorShift(0x4528_21e6_38d0_1377);
+    let a = ix {
+        sign: true,
+        vals: rng.limbs(300),
+    };
+    let b = ix {
+        sign: false,
+        vals: rng.limbs(250),
+    };
+    let ab = mul_ix(&a, &b);
+    assert!(ab.sign);
+    assert_eq!(ab.vals, mul_schoolbook(&a.vals, &b.vals));
+    let aa = mul_ix(&a, &a);
+    assert!(!aa.sign);
+    assert_eq!(aa, sqr_ix(&a));
+    assert_eq!(aa.vals, mul_schoolbook(&a.vals, &a.vals));
+}
@@ -0,0 +1,135 @@
+use bignum::ix;
+
+fn n(s: &str) -> ix {
+    s.parse().unwrap()
+}
+
+#[test]
+fn factorial_table() {
+    let table = [
+        1u64, 1, 2, 6, 24, 120, 720, 5040, 40320, 362880, 3628800, 39916800, 479001600,
+    ];
+    for (k, &f) in table.iter().enumerate() {
+        assert_eq!(ix::factorial(k as u64), ix::from(f), "{}!", k);
+    }
+    assert_eq!(ix::factorial(20), ix::from(2432902008176640000u64));
+    assert_eq!(ix::factorial(30), n("265252859812191058636308480000000"));
+
+    // Against the running product, past the product tree's leaf size.
+    let mut expected = ix::from(1u64);
+    for k in 1..=300u64 {
+        expected = &expected * &ix::from(k);
+        assert_eq!(ix::factorial(k), expected, "{}!", k);
+    }
+}
+
+#[test]
+fn binomial_table() {
+    // Pascal's triangle, compared row by row.
+    let mut row = vec![ix::from(1u64)];
+    for n in 0..=120u64 {
+        for (k, c) in row.iter().enumerate() {
+            assert_eq!(&ix::binomial(n, k as u64), c, "C({}, {})", n, k);
+        }
+        assert_eq!(ix::binomial(n, n + 1), ix::from(0u64));
+        let mut next = vec![ix::from(1u64)];
+        for w in row.windows(2) {
+            next.push(&w[0] + &w[1]);
+        }
+        next.push(ix::from(1u64));
+        row = next;
+    }
+    assert_eq!(ix::binomial(10, 3), ix::from(120u64));
+    assert_eq!(ix::binomial(52, 5), ix::from(2598960u64));
+    assert_eq!(ix::binomial(100, 50), n("100891344545564193334812497256"));
+    // Small k against a large n takes the falling-factorial path.
+    assert_eq!(
+        ix::binomial(1_000_000_000_000, 3),
+        n("166666666666166666666667000000000000")
+    );
+}
+
+#[test]
+fn fibonacci_table() {
+    let table = [
+        0u64, 1, 1, 2, 3, 5, 8, 13, 21, 34, 55, 89, 144, 233, 377, 610,
+    ];
+    for (k, &f) in table.iter().enumerate() {
+        assert_eq!(ix::fibonacci(k as u64), ix::from(f), "F({})", k);
+    }
+    let (mut a, mut b) = (ix::from(0u64), ix::from(1u64));
+    for k in 0..=500u64 {
+        assert_eq!(ix::fibonacci(k), a, "F({})", k);
+        (a, b) = (b.clone(), &a + &b);
+    }
+    assert_eq!(ix::fibonacci(93), ix::from(12200160415121876738u64));
+}
+
+#[test]
+fn crt_reconstruction() {
+    let c = |pairs: &[(i64, i64)]| {
+        let pairs: Vec<_> = pairs
+            .iter()
+            .map(|&(r, m)| (ix::from(r), ix::from(m)))
+            .collect();
+        ix::crt(&pairs)
+    };
+    let sol = |x: i64, l: i64| Some((ix::from(x), ix::from(l)));
+
+    assert_eq!(c(&[]), sol(0, 1));
+    assert_eq!(c(&[(2, 3), (3, 5), (2, 7)]), sol(23, 105));
+    assert_eq!(c(&[(-1, 4), (10, 9)]), sol(19, 36));
+    // Non-coprime moduli: consistent and inconsistent.
+    assert_eq!(c(&[(3, 4), (5, 6)]), sol(11, 12));
+    assert_eq!(c(&[(1, 4), (2, 6)]), None);
+    assert_eq!(c(&[(7, 10), (7, 10)]), sol(7, 10));
+
+    // Every residue pattern over a set of small moduli.
+    let moduli = [3i64, 4, 5, 7];
+    for x in 0..420 {
+        let pairs: Vec<_> = moduli.iter().map(|&m| (x % m, m)).collect();
+        assert_eq!(c(&pairs), sol(x, 420));
+    }
+
+    // Many large prime moduli.
+    let primes = [
+        "1000000007",
+        "998244353",
+        "18446744073709551557",
+        "2305843009213693951",
+    ];
+    let x = n("123456789012345678901234567890123456789012345678901234567890");
+    let pairs: Vec<_> = primes.iter().map(|p| (x.rem_floor(&n(p)), n(p))).collect();
+    let l = primes.iter().fold(ix::from(1u64), |acc, p| &acc * &n(p));
+    let expected = x.rem_floor(&l);
+    assert_eq!(ix::crt(&pairs), Some((expected, l)));
+}
+
+#[test]
+#[should_panic(expected = "non-positive modulus")]
+fn crt_rejects_zero_modulus() {
+    ix::crt(&[(ix::from(1u64), ix::from(0u64))]);
+}
+
+#[test]
+fn integer_log_table() {
+    for base in 2..=12u64 {
+        let b = ix::from(base);
+        for x in 1..=2000u64 {
+            let expected = x.ilog(base) as u64;
+            assert_eq!(ix::from(x).ilog(&b), expected, "log_{}({})", base, x);
+        }
+    }
+    let ten = ix::from(10u64);
+    for k in 0..200u32 {
+        let p = ten.pow(k);
+        assert_eq!(p.ilog(&ten), k as u64);
+        if k > 0 {
+            assert_eq!((&p - ix::from(1u64)).ilog(&ten), k as u64 - 1);
+        }
+    }
+    assert_eq!((ix::from(1u64) << 80).ilog2(), 80);
+    assert_eq!(ix::from(0u64).checked_ilog(&ten), None);
+    assert_eq!(ix::from(-5i64).checked_ilog(&ten), None);
+    assert_eq!(ix::from(5u64).checked_ilog(&ix::from(1u64)), None);
+}
@@ -0,0 +1,127 @@
+use std::cmp::Ordering;
+
+use bignum::{TryFromIxError, ix};
+
+fn n(v: i128) -> ix {
+    ix::from(v)
+}
+
+#[test]
+fn try_from_at_the_bounds() {
+    let out_of_range: TryFromIxError = u64::try_from(n(-1)).unwrap_err();
+    assert_eq!(i64::try_from(n(i64::MIN as i128)), Ok(i64::MIN));
+    assert_eq!(i64::try_from(n(i64::MAX as i128)), Ok(i64::MAX));
+    assert_eq!(i64::try_from(n(i64::MAX as i128 + 1)), Err(out_of_range));
+    assert!(i64::try_from(n(i64::MIN as i128 - 1)).is_err());
+
+    assert_eq!(u64::try_from(n(u64::MAX as i128)), Ok(u64::MAX));
+    assert!(u64::try_from(n(u64::MAX as i128 + 1)).is_err());
+    assert!(u64::try_from(n(-1)).is_err());
+    assert_eq!(u64::try_from(n(0)), Ok(0));
+
+    assert_eq!(i128::try_from(n(i128::MIN)), Ok(i128::MIN));
+    assert_eq!(i128::try_from(n(i128::MAX)), Ok(i128::MAX));
+    assert!(i128::try_from(n(i128::MAX) + n(1)).is_err());
+    assert!(i128::try_from(n(i128::MIN) - n(1)).is_err());
+
+    assert_eq!(u128::try_from(ix::from(u128::MAX)), Ok(u128::MAX));
+    assert!(u128::try_from(ix::from(u128::MAX) + n(1)).is_err());
+    assert!(u128::try_from(n(-1)).is_err());
+
+    // By reference too, and the error has a message.
+    assert_eq!(i64::try_from(&n(-5)), Ok(-5));
+    let err = u64::try_from(&n(-5)).unwrap_err();
+    assert_eq!(err.to_string(), "ix out of range for target integer type");
+}
+
+#[test]
+fn from_primitives_round_trip() {
+    for v in [0i64, 1, -1, i64::MIN, i64::MAX] {
+        assert_eq!(i64::try_from(ix::from(v)), Ok(v));
+    }
+    for v in [0u64, 1, u64::MAX] {
+        assert_eq!(u64::try_from(ix::from(v)), Ok(v));
+    }
+    assert_eq!(ix::from(0i64), ix::from(0u128));
+    assert_eq!(ix::from(-0i128).to_string(), "0");
+}
+
+#[test]
+fn ordering_across_signs_and_zero() {
+    let big = n(1) << 100;
+    let sorted = [
+        -&big,
+        n(-(1 << 64)),
+        n(-1),
+        n(0),
+        n(1),
+        n(1 << 64),
+        big.clone(),
+    ];
+    for (i, a) in sorted.iter().enumerate() {
+        for (j, b) in sorted.iter().enumerate() {
+            assert_eq!(a.cmp(b), i.cmp(&j), "{} vs {}", a, b);
+            assert_eq!(a.partial_cmp(b), Some(i.cmp(&j)));
+        }
+    }
+    assert_eq!(-n(0), n(0));
+    assert_eq!((-n(0)).cmp(&n(0)), Ordering::Equal);
+    assert_eq!(sorted.iter().max(), Some(&big));
+    assert!(n(-2) < n(-1) && n(-1) < n(0));
+}
+
+#[test]
+fn arithmetic_operators() {
+    let (a, b) = (n(-7), n(2));
+    assert_eq!(&a + &b, n(-5));
+    assert_eq!(&a - &b, n(-9));
+    assert_eq!(&a * &b, n(-14));
+    assert_eq!(&a / &b, n(-4));
+    assert_eq!(&a % &b, n(1));
+    assert_eq!(a.clone() + b.clone(), a.clone() + &b);
+    assert_eq!(&a + b.clone(), n(-5));
+    assert_eq!(-&a, n(7));
+
+    let mut x = n(10);
+    x += n(5);
+    x -= &n(3);
+    x *= n(4);
+    x /= n(-5);
+    assert_eq!(x, n(-10));
+    x %= n(3);
+    assert_eq!(x, n(2));
+    assert_eq!(&n(5) - &n(5), n(0));
+}
+
+#[test]
+fn display_and_hex_of_negatives() {
+    let x = n(-255);
+    assert_eq!(x.to_string(), "-255");
+    assert_eq!(format!("{:x}", x), "-ff");
+    assert_eq!(format!("{:#x}", x), "-0xff");
+    assert_eq!(format!("{:X}", x), "-FF");
+    assert_eq!(format!("{:#X}", x), "-0xFF");
+    assert_eq!(format!("{:+}", n(255)), "+255");
+    assert_eq!(format!("{:>8}", x), "    -255");
+    assert_eq!(format!("{:08}", x), "-0000255");
+    assert_eq!(format!("{:#010x}", x), "-0x00000ff");
+
+    // Limbs below the top one are zero-padded.
+    let y = -(n(1) << 64) - n(0xab);
+    assert_eq!(format!("{:#x}", y), "-0x100000000000000ab");
+    assert_eq!(format!("{:X}", y), "-100000000000000AB");
+    assert_eq!(format!("{:x}", n(0)), "0");
+    assert_eq!(format!("{:#x}", n(0)), "0x0");
+}
+
+#[test]
+fn empty_sum_and_product() {
+    assert_eq!(std::iter::empty::<ix>().sum::<ix>(), n(0));
+    assert_eq!(std::iter::empty::<ix>().product::<ix>(), n(1));
+    assert_eq!((1..=5).map(n).sum::<ix>(), n(15));
+    assert_eq!(
+        (1..=20).map(|i| n(-i)).product::<ix>(),
+        n(2432902008176640000)
+    );
+    assert_eq!([n(3), n(-3)].into_iter().sum::<ix>(), n(0));
+}
@@ -0,0 +1,217 @@
+use bignum::{RandomSource, is_prime_u64, ix, random_prime};
+
+struct XorShift(u64);
+
+impl RandomSource for XorShift {
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+fn n(s: &str) -> ix {
+    s.parse().unwrap()
+}
+
+fn mersenne(p: usize) -> ix {
+    (ix::from(1u64) << p) - ix::from(1u64)
+}
+
+/// Composites that fool weaker tests: Carmichael numbers, and strong
+/// pseudoprimes to base 2 (3215031751 also to bases 3, 5 and 7).
+const PSEUDOPRIMES: [u64; 8] = [
+    561,
+    1105,
+    2047,
+    1373653,
+    25326001,
+    3215031751,
+    2152302898747,
+    3825123056546413051,
+];
+
+#[test]
+fn agrees_with_a_sieve_below_a_million() {
+    const LIMIT: usize = 1_000_000;
+    let mut composite = vec![false; LIMIT];
+    composite[0] = true;
+    composite[1] = true;
+    for i in 2..LIMIT {
+        if !composite[i] {
+            for j in (i * i..LIMIT).step_by(i) {
+                composite[j] = true;
+            }
+        }
+    }
+    for (i, &c) in composite.iter().enumerate() {
+        assert_eq!(is_prime_u64(i as u64), !c, "{}", i);
+    }
+    // The bignum path on a sample, since it defers to is_prime_u64.
+    for i in (0..LIMIT).step_by(97) {
+        assert_eq!(
+            ix::from(i as u64).is_probable_prime(),
+            !composite[i],
+            "{}",
+            i
+        );
+    }
+}
+
+#[test]
+fn u64_edges() {
+    assert!(is_prime_u64(18446744073709551557)); // largest u64 prime
+    assert!(!is_prime_u64(u64::MAX));
+    assert!(!is_prime_u64(4294967291 * 4294967279));
+    assert!(is_prime_u64((1 << 61) - 1));
+    for p in PSEUDOPRIMES {
+        assert!(!is_prime_u64(p), "{}", p);
+    }
+}
+
+#[test]
+fn pseudoprimes_are_rejected() {
+    let mut rng = XorShift(0x2545f4914f6cdd1d);
+    for p in PSEUDOPRIMES {
+        let x = ix::from(p);
+        assert!(!x.is_probable_prime(), "{}", p);
+        assert!(!x.miller_rabin(20, &mut rng), "{}", p);
+    }
+    // p·(2p - 1) with 2p - 1 ≡ ±1 mod 8: strong pseudoprimes to base 2
+    // whose factors are past the trial-division table, so only the Lucas
+    // half of Baillie–PSW can reject them.
+    for s in ["2417851664969925135785653", "2417851667397646823447341"] {
+        let x = n(s);
+        assert!(!x.is_probable_prime(), "{}", s);
+        assert!(!x.miller_rabin(20, &mut rng), "{}", s);
+    }
+    // A Carmichael number past 2^64: (6k + 1)(12k + 1)(18k + 1).
+    let k = ix::from(1_000_000_000_000u64);
+    let f = [6u64, 12, 18].map(|c| &k * ix::from(c) + ix::from(1u64));
+    assert!(!(&f[0] * &f[1] * &f[2]).is_probable_prime());
+}
+
+#[test]
+fn known_large_primes_are_accepted() {
+    let mut rng = XorShift(0x9e3779b97f4a7c15);
+    for p in [61, 89, 107, 127, 521, 607, 1279] {
+        let m = mersenne(p);
+        assert!(m.is_probable_prime(), "2^{} - 1", p);
+        assert!(m.miller_rabin(5, &mut rng), "2^{} - 1", p);
+    }
+    for p in [67, 101, 257] {
+        assert!(!mersenne(p).is_probable_prime(), "2^{} - 1", p);
+    }
+    // 2^64 + 13 is the smallest prime above 2^64.
+    let base = ix::from(1u64) << 64;
+    assert!((&base + ix::from(13u64)).is_probable_prime());
+    for d in 0..13u64 {
+        assert!(!(&base + ix::from(d)).is_probable_prime(), "2^64 + {}", d);
+    }
+    assert!(n("170141183460469231731687303715884105727").is_probable_prime());
+    assert!(!n("-7").is_probable_prime());
+    assert!(!n("0").is_probable_prime());
+}
+
+#[test]
+fn miller_rabin_small_values() {
+    let mut rng = XorShift(1);
+    for (v, prime) in [
+        (0, false),
+        (1, false),
+        (2, true),
+        (3, true),
+        (4, false),
+        (5, true),
+    ] {
+        assert_eq!(ix::from(v as u64).miller_rabin(4, &mut rng), prime, "{}", v);
+    }
+    assert!(!ix::from(-3i64).miller_rabin(4, &mut rng));
+}
+
+#[test]
+fn jacobi_table() {
+    // Rows a = 0..=8, columns n = 1, 3, 5, 7, 9, 11.
+    let table: [[i32; 6]; 9] = [
+        [1, 0, 0, 0, 0, 0],
+        [1, 1, 1, 1, 1, 1],
+        [1, -1, -1, 1, 1, -1],
+        [1, 0, -1, -1, 0, 1],
+        [1, 1, 1, 1, 1, 1],
+        [1, -1, 0, -1, 1, 1],
+        [1, 0, 1, -1, 0, -1],
+        [1, 1, -1, 0, 1, -1],
+        [1, -1, -1, 1, 1, -1],
+    ];
+    for (a, row) in table.iter().enumerate() {
+        for (&m, &j) in [1u64, 3, 5, 7, 9, 11].iter().zip(row) {
+            assert_eq!(ix::from(a as u64).jacobi(&ix::from(m)), j, "({}/{})", a, m);
+        }
+    }
+    assert_eq!(ix::from(-1i64).jacobi(&ix::from(7u64)), -1);
+    assert_eq!(ix::from(-1i64).jacobi(&ix::from(13u64)), 1);
+    assert_eq!(ix::from(1001u64).jacobi(&ix::from(9907u64)), -1);
+
+    // Euler's criterion against a large prime.
+    let p = mersenne(127);
+    let half = (&p - ix::from(1u64)) >> 1;
+    for a in [2u64, 3, 5, 12345, u64::MAX] {
+        let a = ix::from(a);
+        let euler = a.pow_mod(&half, &p);
+        let expected = if euler == ix::from(1u64) { 1 } else { -1 };
+        assert_eq!(a.jacobi(&p), expected, "({}/p)", a);
+    }
+}
+
+#[test]
+#[should_panic(expected = "positive odd modulus")]
+fn jacobi_even_modulus_panics() {
+    ix::from(3u64).jacobi(&ix::from(8u64));
+}
+
+#[test]
+fn next_prime_values() {
+    for (x, p) in [
+        (-10i64, 2u64),
+        (0, 2),
+        (1, 2),
+        (2, 3),
+        (3, 5),
+        (13, 17),
+        (24, 29),
+        (89, 97),
+        (1_000_000, 1_000_003),
+        (2147483647, 2147483659),
+    ] {
+        assert_eq!(ix::from(x).next_prime(), ix::from(p), "after {}", x);
+    }
+    let base = ix::from(1u64) << 64;
+    assert_eq!(
+        (&base - ix::from(60u64)).next_prime(),
+        ix::from(u64::MAX - 58)
+    );
+    assert_eq!(
+        (&base - ix::from(58u64)).next_prime(),
+        &base + ix::from(13u64)
+    );
+    assert_eq!((mersenne(127) - ix::from(2u64)).next_prime(), mersenne(127));
+}
+
+#[test]
+fn random_prime_has_the_requested_bits() {
+    let mut rng = XorShift(0xdeadbeefcafef00d);
+    for bits in [2, 3, 8, 63, 64, 65, 128, 256] {
+        for _ in 0..3 {
+            let p = random_prime(bits, &mut rng);
+            assert_eq!(p.bit_len(), bits, "{}", p);
+            assert!(p.is_probable_prime(), "{}", p);
+        }
+    }
+}
+
+#[test]
+#[should_panic(expected = "fewer than 2 bits")]
+fn random_prime_needs_two_bits() {
+    random_prime(1, &mut XorShift(1));
+}
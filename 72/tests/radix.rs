@@ -0,0 +1,180 @@
+use bignum::{ParseIxError, ix};
+use num_bigint::{BigInt, Sign};
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn limbs(&mut self, n: usize) -> Vec<u64> {
+        let mut v: Vec<u64> = (0..n).map(|_| self.next()).collect();
+        if let Some(top) = v.last_mut() {
+            *top |= 1;
+        }
+        v
+    }
+}
+
+fn to_big(x: &ix) -> BigInt {
+    let words: Vec<u32> = x
+        .vals
+        .iter()
+        .flat_map(|&v| [v as u32, (v >> 32) as u32])
+        .collect();
+    let sign = if x.sign { Sign::Minus } else { Sign::Plus };
+    BigInt::from_slice(sign, &words)
+}
+
+fn from_limbs(vals: Vec<u64>, sign: bool) -> ix {
+    ix { sign, vals }
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!("".parse::<ix>(), Err(ParseIxError::Empty));
+    assert_eq!(ix::from_str_radix("", 16), Err(ParseIxError::Empty));
+    for s in ["-", "+", "--1", "+-1", "-+1", "1-", "1-2", "12+3"] {
+        assert_eq!(s.parse::<ix>(), Err(ParseIxError::StraySign), "{:?}", s);
+    }
+    assert_eq!("12a".parse::<ix>(), Err(ParseIxError::InvalidDigit('a')));
+    assert_eq!(" 1".parse::<ix>(), Err(ParseIxError::InvalidDigit(' ')));
+    assert_eq!("1_000".parse::<ix>(), Err(ParseIxError::InvalidDigit('_')));
+    assert_eq!("١".parse::<ix>(), Err(ParseIxError::InvalidDigit('١')));
+    assert_eq!(
+        ix::from_str_radix("0x10", 16),
+        Err(ParseIxError::InvalidDigit('x'))
+    );
+    assert_eq!(
+        ix::from_str_radix("102", 2),
+        Err(ParseIxError::InvalidDigit('2'))
+    );
+    assert_eq!(
+        ix::from_str_radix("z", 35),
+        Err(ParseIxError::InvalidDigit('z'))
+    );
+    // The first bad character is reported, even past the DC threshold.
+    let long = format!("{}g{}", "9".repeat(2000), "!".repeat(10));
+    assert_eq!(long.parse::<ix>(), Err(ParseIxError::InvalidDigit('g')));
+
+    assert_eq!(
+        ParseIxError::Empty.to_string(),
+        "cannot parse ix from empty string"
+    );
+    assert_eq!(
+        ParseIxError::InvalidDigit('q').to_string(),
+        "invalid digit 'q' in ix literal"
+    );
+    assert_eq!(
+        ParseIxError::StraySign.to_string(),
+        "misplaced sign in ix literal"
+    );
+}
+
+#[test]
+fn accepted_forms() {
+    assert_eq!("+42".parse::<ix>(), Ok(ix::from(42u64)));
+    assert_eq!("-0".parse::<ix>(), Ok(ix::from(0u64)));
+    assert!(!"-000".parse::<ix>().unwrap().sign);
+    assert_eq!("007".parse::<ix>(), Ok(ix::from(7u64)));
+    assert_eq!(ix::from_str_radix("Zz", 36), Ok(ix::from(36u64 * 35 + 35)));
+    assert_eq!(ix::from_str_radix("-FfFf", 16), Ok(ix::from(-0xffffi64)));
+    assert_eq!(ix::from(-255i64).to_str_radix(16), "-ff");
+    assert_eq!(ix::from(0u64).to_str_radix(36), "0");
+}
+
+#[test]
+#[should_panic(expected = "radix must be in 2..=36")]
+fn radix_out_of_range() {
+    ix::from(1u64).to_str_radix(37);
+}
+
+/// Sizes in limbs around the divide-and-conquer threshold of 32 limbs.
+const SIZES: [usize; 9] = [1, 2, 31, 32, 33, 63, 64, 65, 130];
+
+#[test]
+fn round_trips_in_every_radix() {
+    let mut rng = XorShift(0x2545f4914f6cdd1d);
+    for radix in 2..=36 {
+        for &len in &SIZES {
+            for sign in [false, true] {
+                let x = from_limbs(rng.limbs(len), sign);
+                let s = x.to_str_radix(radix);
+                assert_eq!(s, to_big(&x).to_str_radix(radix), "radix {}", radix);
+                assert_eq!(ix::from_str_radix(&s, radix), Ok(x.clone()));
+                let upper = s.to_uppercase();
+                assert_eq!(ix::from_str_radix(&upper, radix), Ok(x));
+            }
+        }
+    }
+}
+
+#[test]
+fn digit_strings_around_the_parse_threshold() {
+    // Parsing splits strings longer than 32 chunks of digits.
+    let mut rng = XorShift(0x9e3779b97f4a7c15);
+    for radix in [3, 7, 10, 16, 36] {
+        let chunk = (u64::MAX as f64).log(radix as f64) as usize;
+        for len in [
+            32 * chunk - 1,
+            32 * chunk,
+            32 * chunk + 1,
+            64 * chunk + 5,
+            300 * chunk,
+        ] {
+            let s: String = (0..len)
+                .map(|i| {
+                    let d = if i == 0 {
+                        1 + rng.next() % (radix as u64 - 1)
+                    } else {
+                        rng.next() % radix as u64
+                    };
+                    std::char::from_digit(d as u32, radix).unwrap()
+                })
+                .collect();
+            let x = ix::from_str_radix(&s, radix).unwrap();
+            assert_eq!(
+                to_big(&x),
+                BigInt::parse_bytes(s.as_bytes(), radix).unwrap()
+            );
+            assert_eq!(x.to_str_radix(radix), s);
+        }
+    }
+}
+
+#[test]
+fn runs_of_zero_digits_survive_splitting() {
+    // The low half of every split is padded back to its full width.
+    for radix in [2, 3, 10, 36] {
+        let r = ix::from(radix as u64);
+        for k in [1, 100, 617, 1233, 2500] {
+            let p = r.pow(k);
+            let s = p.to_str_radix(radix);
+            assert_eq!(s.len(), k as usize + 1);
+            assert!(s.starts_with('1') && s[1..].bytes().all(|b| b == b'0'));
+            let below = &p - ix::from(1u64);
+            let top = std::char::from_digit(radix - 1, radix).unwrap();
+            assert!(below.to_str_radix(radix).chars().all(|c| c == top));
+            let sparse = &p * &p + ix::from(1u64);
+            let s = sparse.to_str_radix(radix);
+            assert_eq!(s.len(), 2 * k as usize + 1);
+            assert_eq!(ix::from_str_radix(&s, radix), Ok(sparse));
+        }
+    }
+}
+
+#[test]
+fn large_values_match_num_bigint() {
+    // Big enough that the powers of the radix take Newton reciprocals.
+    let mut rng = XorShift(0xdeadbeefcafef00d);
+    for (radix, len) in [(10, 3000), (7, 1500), (36, 700)] {
+        let x = from_limbs(rng.limbs(len), true);
+        let s = x.to_str_radix(radix);
+        assert_eq!(s, to_big(&x).to_str_radix(radix));
+        assert_eq!(ix::from_str_radix(&s, radix), Ok(x));
+    }
+}
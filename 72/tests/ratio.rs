@@ -0,0 +1,126 @@
+use bignum::{IxError, ParseIxError, Ratio, RoundingMode, ix};
+
+fn r(s: &str) -> Ratio {
+    s.parse().unwrap()
+}
+
+#[test]
+fn always_reduced_with_positive_denominator() {
+    let x = Ratio::new(ix::from(6i64), ix::from(-8i64));
+    assert_eq!(x.numer(), &ix::from(-3i64));
+    assert_eq!(x.denom(), &ix::from(4i64));
+    assert_eq!(x, r("-3/4"));
+    assert_eq!(Ratio::new(ix::from(0i64), ix::from(-5i64)), Ratio::zero());
+    assert_eq!(
+        Ratio::checked_new(ix::from(1i64), ix::from(0i64)),
+        Err(IxError::DivisionByZero)
+    );
+}
+
+#[test]
+fn arithmetic_and_ordering() {
+    assert_eq!(r("1/2") + r("1/3"), r("5/6"));
+    assert_eq!(r("1/2") - r("3/4"), r("-1/4"));
+    assert_eq!(r("-2/3") * r("9/4"), r("-3/2"));
+    assert_eq!(r("1/2") / r("-1/4"), r("-2"));
+    assert_eq!(-r("1/2"), r("-1/2"));
+    assert_eq!(r("2/7").recip(), r("7/2"));
+    assert_eq!(r("0").checked_recip(), Err(IxError::DivisionByZero));
+    let mut acc = Ratio::zero();
+    for k in 1..=10i64 {
+        acc += Ratio::new(ix::from(1i64), ix::from(k * (k + 1)));
+    }
+    assert_eq!(acc, r("10/11"));
+    assert!(r("-1/2") < r("-1/3"));
+    assert!(r("2/3") > r("0.666"));
+    assert_eq!(r("5/10").to_string(), "1/2");
+    assert_eq!(r("-8/4").to_string(), "-2");
+}
+
+#[test]
+fn parses_decimals() {
+    assert_eq!(r("-12.375"), r("-99/8"));
+    assert_eq!(r(".5"), r("1/2"));
+    assert_eq!(r("-.5"), r("-1/2"));
+    assert_eq!(r("3."), r("3"));
+    assert_eq!(r("+0.10"), r("1/10"));
+    assert_eq!("1/0".parse::<Ratio>(), Err(IxError::DivisionByZero));
+    assert_eq!("".parse::<Ratio>(), Err(ParseIxError::Empty.into()));
+    assert_eq!(".".parse::<Ratio>(), Err(ParseIxError::Empty.into()));
+    assert_eq!("1/-2".parse::<Ratio>(), Err(ParseIxError::StraySign.into()));
+    assert_eq!("1.-2".parse::<Ratio>(), Err(ParseIxError::StraySign.into()));
+    assert_eq!(
+        "1.2.3".parse::<Ratio>(),
+        Err(ParseIxError::InvalidDigit('.').into())
+    );
+}
+
+#[test]
+fn exponents_are_not_ratio_syntax() {
+    for s in ["2.5e-3", "1e5", "1E5"] {
+        assert!(
+            matches!(
+                s.parse::<Ratio>(),
+                Err(IxError::Parse(ParseIxError::InvalidDigit('e' | 'E')))
+            ),
+            "{}",
+            s
+        );
+    }
+}
+
+#[test]
+fn rounding_modes() {
+    let cases = [
+        // value, floor, ceil, nearest
+        ("7/2", 3, 4, 4),
+        ("5/2", 2, 3, 2),
+        ("-5/2", -3, -2, -2),
+        ("-7/2", -4, -3, -4),
+        ("-1/3", -1, 0, 0),
+        ("2/3", 0, 1, 1),
+        ("-4", -4, -4, -4),
+    ];
+    for (s, floor, ceil, nearest) in cases {
+        let x = r(s);
+        assert_eq!(x.round(RoundingMode::Floor), ix::from(floor as i64), "{s}");
+        assert_eq!(x.round(RoundingMode::Ceil), ix::from(ceil as i64), "{s}");
+        assert_eq!(x.round(RoundingMode::Nearest), ix::from(nearest as i64), "{s}");
+    }
+    // Modes that depend on the sign rather than the direction.
+    assert_eq!(r("-7/2").round(RoundingMode::Down), ix::from(-3i64));
+    assert_eq!(r("-7/2").round(RoundingMode::Up), ix::from(-4i64));
+    assert_eq!(r("-5/2").round(RoundingMode::HalfUp), ix::from(-3i64));
+    assert_eq!(r("5/2").round(RoundingMode::HalfUp), ix::from(3i64));
+    assert_eq!(r("-1/3").round(RoundingMode::Up), ix::from(-1i64));
+}
+
+#[test]
+fn to_f64_is_correctly_rounded() {
+    // For operands below 2^53, IEEE division is itself correctly rounded.
+    let mut state = 0x9e37_79b9_7f4a_7c15u64;
+    for _ in 0..2000 {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        let n = (state >> 11) as i64 - (1 << 52);
+        let d = ((state.rotate_left(29) >> 11) | 1) as i64;
+        let x = Ratio::new(ix::from(n), ix::from(d));
+        assert_eq!(x.to_f64(), n as f64 / d as f64, "{n}/{d}");
+    }
+
+    let pow2 = |k: usize| ix::from(1u64) << k;
+    let frac = |n: ix, d: ix| Ratio::new(n, d).to_f64();
+    assert_eq!(r("1/3").to_f64(), 1.0 / 3.0);
+    assert_eq!(r("-0.1").to_f64(), -0.1);
+    assert_eq!(frac(ix::from(1u64), pow2(1074)), f64::from_bits(1));
+    // Exactly half the smallest subnormal ties to even, which is zero.
+    assert_eq!(frac(ix::from(1u64), pow2(1075)), 0.0);
+    assert_eq!(frac(ix::from(3u64), pow2(1076)), f64::from_bits(1));
+    assert_eq!(frac(pow2(1023), ix::from(1u64)), 2f64.powi(1023));
+    // Halfway between f64::MAX and 2^1024 rounds to even, i.e. overflows.
+    let halfway = pow2(1024) - pow2(970);
+    assert_eq!(frac(&halfway - ix::from(1u64), ix::from(1u64)), f64::MAX);
+    assert_eq!(frac(halfway.clone(), ix::from(1u64)), f64::INFINITY);
+    assert_eq!(frac(-halfway, ix::from(1u64)), f64::NEG_INFINITY);
+}
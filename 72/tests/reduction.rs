@@ -0,0 +1,154 @@
+use bignum::{BarrettCtx, MontgomeryCtx, ix};
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// A random magnitude with exactly `limbs` limbs.
+    fn limbs(&mut self, limbs: usize) -> ix {
+        let mut vals: Vec<u64> = (0..limbs).map(|_| self.next()).collect();
+        vals[limbs - 1] |= 1 << 63;
+        ix { sign: false, vals }
+    }
+
+    /// A random value of either sign below `2^bits`.
+    fn below_bits(&mut self, bits: usize) -> ix {
+        let x = self.limbs(bits.div_ceil(64)) >> (bits.div_ceil(64) * 64 - bits);
+        if self.next() & 1 == 1 { -x } else { x }
+    }
+}
+
+fn n(v: i128) -> ix {
+    ix::from(v)
+}
+
+/// Edge moduli plus values wide enough for Newton reciprocals.
+fn moduli() -> Vec<ix> {
+    let mut rng = XorShift(0x853c49e6748fea9b);
+    let mut out = vec![
+        n(1),
+        n(2),
+        n(3),
+        n(u64::MAX as i128),
+        n(1 << 64),
+        n((1 << 64) + 1),
+        (n(1) << 127) - n(1),
+        n(10).pow(60),
+    ];
+    for limbs in [5, 31, 32, 33, 80] {
+        out.push(rng.limbs(limbs));
+    }
+    out
+}
+
+#[test]
+fn barrett_reduce_matches_rem_floor() {
+    let mut rng = XorShift(0x2545f4914f6cdd1d);
+    for m in moduli() {
+        let ctx = BarrettCtx::new(&m).unwrap();
+        assert_eq!(ctx.modulus(), m);
+        let bits = m.bit_len();
+        // Up to and past 2^(128·k), where reduction falls back to division.
+        for width in [
+            1,
+            bits - 1,
+            bits,
+            bits + 1,
+            2 * bits,
+            128 * bits.div_ceil(64) + 64,
+        ] {
+            for _ in 0..4 {
+                let x = rng.below_bits(width.max(1));
+                assert_eq!(ctx.reduce(&x), x.rem_floor(&m), "{} mod {}", x, m);
+            }
+        }
+        let below = &m - n(1);
+        assert_eq!(ctx.reduce(&below), below);
+        assert_eq!(ctx.reduce(&m), n(0));
+        assert_eq!(ctx.reduce(&-&m), n(0));
+        assert_eq!(ctx.reduce(&(&m * &m - n(1))), &m - n(1));
+        assert_eq!(ctx.reduce(&n(-1)), &m - n(1));
+    }
+}
+
+#[test]
+fn barrett_products_match_multiply_then_reduce() {
+    let mut rng = XorShift(0x9e3779b97f4a7c15);
+    for m in moduli() {
+        let ctx = BarrettCtx::new(&m).unwrap();
+        let bits = m.bit_len();
+        for _ in 0..8 {
+            let a = rng.below_bits(bits).rem_floor(&m);
+            let b = rng.below_bits(bits).rem_floor(&m);
+            assert_eq!(ctx.mul(&a, &b), (&a * &b).rem_floor(&m));
+            assert_eq!(ctx.sqr(&a), (&a * &a).rem_floor(&m));
+        }
+        let top = &m - n(1);
+        assert_eq!(ctx.sqr(&top), (&top * &top).rem_floor(&m));
+    }
+}
+
+#[test]
+fn barrett_rejects_non_positive_moduli() {
+    assert!(BarrettCtx::new(&n(0)).is_none());
+    assert!(BarrettCtx::new(&n(-7)).is_none());
+}
+
+#[test]
+fn montgomery_matches_multiply_then_reduce() {
+    let mut rng = XorShift(0xdeadbeefcafef00d);
+    for m in moduli()
+        .into_iter()
+        .filter(|m| m.vals[0] & 1 == 1 && *m != n(1))
+    {
+        let ctx = MontgomeryCtx::new(&m).unwrap();
+        assert_eq!(ctx.modulus(), m);
+        let r = n(1) << (64 * m.vals.len());
+        let bits = m.bit_len();
+        for _ in 0..8 {
+            let a = rng.below_bits(bits + 3);
+            let b = rng.below_bits(bits).rem_floor(&m);
+            let (am, bm) = (ctx.to_mont(&a), ctx.to_mont(&b));
+            assert_eq!(am, (&a * &r).rem_floor(&m), "to_mont({}) mod {}", a, m);
+            assert_eq!(ctx.from_mont(&am), a.rem_floor(&m));
+            assert_eq!(ctx.from_mont(&ctx.mul(&am, &bm)), (&a * &b).rem_floor(&m));
+            assert_eq!(ctx.from_mont(&ctx.sqr(&am)), (&a * &a).rem_floor(&m));
+        }
+        let top = &m - n(1);
+        let tm = ctx.to_mont(&top);
+        assert_eq!(ctx.from_mont(&ctx.sqr(&tm)), n(1));
+        assert_eq!(ctx.from_mont(&ctx.to_mont(&n(0))), n(0));
+    }
+}
+
+#[test]
+fn montgomery_rejects_even_and_unit_moduli() {
+    for m in [n(0), n(1), n(2), n(1 << 64), n(-3)] {
+        assert!(MontgomeryCtx::new(&m).is_none(), "modulus {}", m);
+    }
+}
+
+#[test]
+fn pow_agrees_across_contexts() {
+    let mut rng = XorShift(0x0123456789abcdef);
+    for m in moduli() {
+        let barrett = BarrettCtx::new(&m).unwrap();
+        let mont = MontgomeryCtx::new(&m);
+        for _ in 0..3 {
+            let base = rng.below_bits(m.bit_len() + 10);
+            let exp = rng.below_bits(90);
+            let exp = if exp.sign { -exp } else { exp };
+            let want = base.pow_mod(&exp, &m);
+            assert_eq!(barrett.pow(&base, &exp), want);
+            if let Some(ctx) = &mont {
+                assert_eq!(ctx.pow(&base, &exp), want);
+            }
+        }
+    }
+}
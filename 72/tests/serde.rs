@@ -0,0 +1,87 @@
+#![cfg(feature = "serde")]
+
+use bignum::ix;
+
+fn n(s: &str) -> ix {
+    s.parse().unwrap()
+}
+
+fn samples() -> Vec<ix> {
+    vec![
+        ix::from(0i64),
+        ix::from(1i64),
+        ix::from(-1i64),
+        ix::from(u64::MAX),
+        n("-340282366920938463463374607431768211456"),
+        n("123456789012345678901234567890123456789012345678901234567890"),
+    ]
+}
+
+#[test]
+fn json_uses_decimal_strings() {
+    assert_eq!(
+        serde_json::to_string(&n("-12345678901234567890123")).unwrap(),
+        "\"-12345678901234567890123\""
+    );
+    for x in samples() {
+        let s = serde_json::to_string(&x).unwrap();
+        assert_eq!(serde_json::from_str::<ix>(&s).unwrap(), x);
+    }
+    let v: Vec<ix> =
+        serde_json::from_str("[\"42\", \"-0x1f\", \"0xFFFFFFFFFFFFFFFFFF\", 7, -9]").unwrap();
+    assert_eq!(
+        v,
+        [
+            ix::from(42i64),
+            ix::from(-31i64),
+            n("4722366482869645213695"),
+            ix::from(7i64),
+            ix::from(-9i64)
+        ]
+    );
+    for bad in [
+        "\"\"", "\"12a\"", "\"0x\"", "\"0x-5\"", "\"--1\"", "1.5", "true",
+    ] {
+        assert!(serde_json::from_str::<ix>(bad).is_err(), "{}", bad);
+    }
+}
+
+#[test]
+fn json_hex_helper() {
+    let mut out = Vec::new();
+    bignum::serde_hex::serialize(
+        &ix::from(-255i64),
+        &mut serde_json::Serializer::new(&mut out),
+    )
+    .unwrap();
+    assert_eq!(out, b"\"-0xff\"");
+    for x in samples() {
+        let mut out = Vec::new();
+        bignum::serde_hex::serialize(&x, &mut serde_json::Serializer::new(&mut out)).unwrap();
+        let mut de = serde_json::Deserializer::from_slice(&out);
+        assert_eq!(bignum::serde_hex::deserialize(&mut de).unwrap(), x);
+    }
+}
+
+#[test]
+fn bincode_uses_limbs() {
+    let x = n("-18446744073709551617");
+    let bytes = bincode::serialize(&x).unwrap();
+    let mut expected = vec![1u8];
+    expected.extend(2u64.to_le_bytes());
+    expected.extend(1u64.to_le_bytes());
+    expected.extend(1u64.to_le_bytes());
+    assert_eq!(bytes, expected);
+    for x in samples() {
+        let bytes = bincode::serialize(&x).unwrap();
+        assert_eq!(bincode::deserialize::<ix>(&bytes).unwrap(), x);
+    }
+}
+
+#[test]
+fn bincode_rejects_non_canonical() {
+    let leading_zero = bincode::serialize(&(false, vec![5u64, 0])).unwrap();
+    assert!(bincode::deserialize::<ix>(&leading_zero).is_err());
+    let negative_zero = bincode::serialize(&(true, Vec::<u64>::new())).unwrap();
+    assert!(bincode::deserialize::<ix>(&negative_zero).is_err());
+}
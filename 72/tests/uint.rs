@@ -0,0 +1,88 @@
+use bignum::{U256, U512, Uint, ix};
+
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    /// Values biased towards zero and all-ones limbs and towards short
+    /// operands, so carries and overflow edges come up often.
+    fn uint<const LIMBS: usize>(&mut self) -> Uint<LIMBS> {
+        let len = (self.next() % (LIMBS as u64 + 1)) as usize;
+        let mut limbs = [0u64; LIMBS];
+        for limb in limbs.iter_mut().take(len) {
+            *limb = match self.next() % 4 {
+                0 => 0,
+                1 => u64::MAX,
+                _ => self.next(),
+            };
+        }
+        Uint { limbs }
+    }
+}
+
+fn modulus<const LIMBS: usize>() -> ix {
+    ix::from(1u64) << (64 * LIMBS)
+}
+
+fn check<const LIMBS: usize>(rng: &mut XorShift) {
+    let (a, b) = (rng.uint::<LIMBS>(), rng.uint::<LIMBS>());
+    let (ia, ib) = (ix::from(&a), ix::from(&b));
+    let m = modulus::<LIMBS>();
+    let wrap = |x: ix| Uint::<LIMBS>::try_from(x % &m).unwrap();
+
+    let sum = &ia + &ib;
+    assert_eq!(a.overflowing_add(&b), (wrap(sum.clone()), sum >= m));
+    let diff = &ia - &ib;
+    assert_eq!(a.overflowing_sub(&b), (wrap(diff.clone()), diff.sign));
+    let prod = &ia * &ib;
+    assert_eq!(a.overflowing_mul(&b), (wrap(prod.clone()), prod >= m));
+    assert_eq!(a.checked_mul(&b).is_some(), prod < m);
+
+    match a.checked_div_rem(&b) {
+        Some((q, r)) => {
+            assert_eq!(ix::from(q), &ia / &ib);
+            assert_eq!(ix::from(r), &ia % &ib);
+        }
+        None => assert!(b.is_zero()),
+    }
+    assert_eq!(a.cmp(&b), ia.cmp(&ib));
+    assert_eq!(Uint::<LIMBS>::try_from(&ia), Ok(a));
+}
+
+#[test]
+fn arithmetic_matches_ix() {
+    let mut rng = XorShift(0x2545_f491_4f6c_dd1d);
+    for _ in 0..5000 {
+        check::<1>(&mut rng);
+        check::<4>(&mut rng);
+        check::<8>(&mut rng);
+    }
+}
+
+#[test]
+fn conversions_and_limits() {
+    assert_eq!(U256::MAX.overflowing_add(&U256::ONE), (U256::ZERO, true));
+    assert_eq!(U256::ZERO.checked_sub(&U256::ONE), None);
+    assert_eq!(U512::MAX.wrapping_mul(&U512::MAX), U512::ONE);
+    assert_eq!(U256::from(7u64) * U256::from(6u64), U256::from(42u64));
+    assert_eq!(U256::BITS, 256);
+
+    let big = modulus::<4>();
+    assert!(U256::try_from(&big).is_err());
+    assert!(U256::try_from(&ix::from(-1i64)).is_err());
+    assert_eq!(ix::from(U256::MAX), big - ix::from(1u64));
+    assert_eq!(U256::from(255u64).to_string(), "255");
+    assert_eq!(format!("{:x}", U512::from(255u64)), "ff");
+}
+
+#[test]
+#[should_panic(expected = "attempt to add with overflow")]
+fn add_operator_panics_on_overflow() {
+    let _ = U256::MAX + U256::ONE;
+}
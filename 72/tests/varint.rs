@@ -0,0 +1,71 @@
+use bignum::{VarintError, ix};
+
+fn n(s: &str) -> ix {
+    s.parse().unwrap()
+}
+
+#[test]
+fn small_values() {
+    for (v, bytes) in [
+        (0i64, vec![0x00]),
+        (-1, vec![0x01]),
+        (1, vec![0x02]),
+        (-2, vec![0x03]),
+        (63, vec![0x7e]),
+        (-64, vec![0x7f]),
+        (64, vec![0x80, 0x01]),
+        (-65, vec![0x81, 0x01]),
+        (300, vec![0xd8, 0x04]),
+    ] {
+        let x = ix::from(v);
+        assert_eq!(x.to_varint(), bytes, "{}", v);
+        assert_eq!(ix::from_varint(&bytes), Ok((x, bytes.len())));
+    }
+}
+
+#[test]
+fn round_trips() {
+    let mut values: Vec<ix> = (-1000i64..=1000).map(ix::from).collect();
+    for k in [7u32, 56, 63, 64, 65, 127, 128, 129, 190, 448, 1000] {
+        let p = ix::from(1u64) << k as usize;
+        for d in [-1i64, 0, 1] {
+            let v = &p + ix::from(d);
+            values.push(-&v);
+            values.push(v);
+        }
+    }
+    values.push(n(
+        "123456789012345678901234567890123456789012345678901234567890",
+    ));
+    for x in &values {
+        let enc = x.to_varint();
+        assert_eq!(ix::from_varint(&enc), Ok((x.clone(), enc.len())), "{}", x);
+        // 7 payload bits per byte, plus the zigzag sign bit.
+        let bits = x.to_str_radix(2).trim_start_matches('-').len() + 1;
+        assert!(enc.len() <= bits.div_ceil(7).max(1), "{}", x);
+    }
+}
+
+#[test]
+fn concatenated_stream() {
+    let values = [ix::from(5i64), n("-98765432109876543210"), ix::from(0i64)];
+    let mut buf = Vec::new();
+    for x in &values {
+        x.write_varint(&mut buf);
+    }
+    let mut rest = &buf[..];
+    for x in &values {
+        let (v, used) = ix::from_varint(rest).unwrap();
+        assert_eq!(&v, x);
+        rest = &rest[used..];
+    }
+    assert!(rest.is_empty());
+}
+
+#[test]
+fn rejects_bad_input() {
+    assert_eq!(ix::from_varint(&[]), Err(VarintError::Truncated));
+    assert_eq!(ix::from_varint(&[0x80, 0xff]), Err(VarintError::Truncated));
+    assert_eq!(ix::from_varint(&[0x82, 0x00]), Err(VarintError::Overlong));
+    assert_eq!(ix::from_varint(&[0x80, 0x00]), Err(VarintError::Overlong));
+}
@@ -4,9 +4,9 @@ use clap::{Parser, Subcommand};
 use hex::encode as hex_encode;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
-use std::io::Read;
+use std::io::{BufRead, Read, Write};
 use std::path::{Path, PathBuf};
 
 /// Simple SCM - minimal commit / add / revert system
@@ -24,6 +24,9 @@ enum Commands {
     Init,
     /// Stage a file for commit
     Add {
+        /// interactively choose hunks to stage
+        #[arg(short = 'p', long = "patch")]
+        patch: bool,
         /// path to file
         path: PathBuf,
     },
@@ -37,13 +40,42 @@ enum Commands {
     Revert,
     /// View commit logs
     Log,
-    /// Show status (staged / modified)
-    Status,
+    /// Show status (staged / modified / untracked)
+    Status {
+        /// porcelain output with two-column XY codes
+        #[arg(short, long)]
+        short: bool,
+        /// also list ignored paths
+        #[arg(long)]
+        ignored: bool,
+    },
     /// Show diff between working copy and HEAD
     Diff {
+        /// ignore whitespace when comparing lines
+        #[arg(short = 'w', long = "ignore-all-space")]
+        ignore_space: bool,
         /// File to diff (optional)
         file: Option<PathBuf>,
     },
+    /// Turn a series of snapshot directories into one commit each, in order,
+    /// and check out the last one
+    ImportSnapshots {
+        /// snapshot directories, oldest first
+        dirs: Vec<PathBuf>,
+        /// file of `<dir> <date> [message]` lines giving the directories,
+        /// their order and commit timestamps (RFC 3339 or YYYY-MM-DD)
+        #[arg(long)]
+        manifest: Option<PathBuf>,
+    },
+    /// Show the history of HEAD updates
+    Reflog,
+    /// Delete objects unreachable from HEAD, the index and recent reflog entries
+    Gc {
+        /// age after which reflog entries and unreachable objects expire
+        /// (e.g. `now`, `never`, `30m`, `12h`, `2w`)
+        #[arg(long, default_value = "2w")]
+        prune: String,
+    },
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -54,23 +86,45 @@ struct Commit {
     timestamp: DateTime<Utc>,
 }
 
+/// One line of `.scm/logs/HEAD`, recording a single move of HEAD.
+#[derive(Serialize, Deserialize, Debug)]
+struct ReflogEntry {
+    old: Option<String>,
+    new: String,
+    timestamp: DateTime<Utc>,
+    command: String,
+}
+
 const SCM_DIR: &str = ".scm";
 const OBJECTS_DIR: &str = ".scm/objects";
 const COMMITS_DIR: &str = ".scm/commits";
 const INDEX_FILE: &str = ".scm/index";
 const HEAD_FILE: &str = ".scm/HEAD";
+const LOGS_DIR: &str = ".scm/logs";
+const HEAD_LOG_FILE: &str = ".scm/logs/HEAD";
+const IGNORE_FILE: &str = ".scmignore";
+const ATTRIBUTES_FILE: &str = ".scmattributes";
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
         Commands::Init => cmd_init(),
-        Commands::Add { path } => cmd_add(&path),
+        Commands::Add { patch, path } => {
+            if patch {
+                cmd_add_patch(&path)
+            } else {
+                cmd_add(&path)
+            }
+        }
         Commands::Commit { message } => cmd_commit(&message),
         Commands::Revert => cmd_revert(),
         Commands::Log => cmd_log(),
-        Commands::Status => cmd_status(),
-        Commands::Diff { file } => cmd_diff(file),
+        Commands::Status { short, ignored } => cmd_status(short, ignored),
+        Commands::Diff { ignore_space, file } => cmd_diff(file, ignore_space),
+        Commands::ImportSnapshots { dirs, manifest } => cmd_import_snapshots(&dirs, manifest),
+        Commands::Reflog => cmd_reflog(),
+        Commands::Gc { prune } => cmd_gc(&prune),
     }
 }
 
@@ -79,15 +133,21 @@ fn cmd_init() -> Result<()> {
         println!("Already initialized.");
         return Ok(());
     }
-    fs::create_dir(SCM_DIR)?;
-    fs::create_dir(OBJECTS_DIR)?;
-    fs::create_dir(COMMITS_DIR)?;
-    fs::write(INDEX_FILE, "")?;
-    fs::write(HEAD_FILE, "")?;
+    init_repo(Path::new("."))?;
     println!("Initialized empty SCM repository in {}", SCM_DIR);
     Ok(())
 }
 
+fn init_repo(repo: &Path) -> Result<()> {
+    fs::create_dir(repo.join(SCM_DIR))?;
+    fs::create_dir(repo.join(OBJECTS_DIR))?;
+    fs::create_dir(repo.join(COMMITS_DIR))?;
+    fs::create_dir(repo.join(LOGS_DIR))?;
+    fs::write(repo.join(INDEX_FILE), "")?;
+    fs::write(repo.join(HEAD_FILE), "")?;
+    Ok(())
+}
+
 fn repo_root() -> Result<std::path::PathBuf> {
     let mut cur = std::env::current_dir()?;
     loop {
@@ -101,7 +161,15 @@ fn repo_root() -> Result<std::path::PathBuf> {
     bail!("Not inside an scm repository (no {}). Run `scm init`", SCM_DIR);
 }
 
-fn load_index(repo: &Path) -> Result<Vec<String>> {
+/// One line of the index. Entries without a blob are snapshotted from the
+/// working copy at commit time; entries with a blob were staged by `add -p`.
+#[derive(Clone, Debug, PartialEq)]
+struct IndexEntry {
+    path: String,
+    blob: Option<String>,
+}
+
+fn load_index(repo: &Path) -> Result<Vec<IndexEntry>> {
     let p = repo.join(INDEX_FILE);
     if !p.exists() {
         return Ok(vec![]);
@@ -110,27 +178,51 @@ fn load_index(repo: &Path) -> Result<Vec<String>> {
     let mut out = vec![];
     for line in s.lines() {
         let trimmed = line.trim();
-        if !trimmed.is_empty() {
-            out.push(trimmed.to_string());
+        if trimmed.is_empty() {
+            continue;
         }
+        let entry = match trimmed.split_once('\t') {
+            Some((path, blob)) => IndexEntry {
+                path: path.to_string(),
+                blob: Some(blob.to_string()),
+            },
+            None => IndexEntry {
+                path: trimmed.to_string(),
+                blob: None,
+            },
+        };
+        out.push(entry);
     }
     Ok(out)
 }
 
-fn write_index(repo: &Path, entries: &[String]) -> Result<()> {
+fn write_index(repo: &Path, entries: &[IndexEntry]) -> Result<()> {
     let p = repo.join(INDEX_FILE);
     let mut s = String::new();
     for e in entries {
-        s.push_str(e);
+        s.push_str(&e.path);
+        if let Some(ref blob) = e.blob {
+            s.push('\t');
+            s.push_str(blob);
+        }
         s.push('\n');
     }
     fs::write(p, s)?;
     Ok(())
 }
 
-fn cmd_add(path: &Path) -> Result<()> {
-    let repo_root = repo_root()?;
-    let repo = repo_root.as_path();
+/// Insert or replace the index entry for `entry.path`, keeping the index sorted.
+fn stage_entry(index: &mut Vec<IndexEntry>, entry: IndexEntry) {
+    match index.iter_mut().find(|e| e.path == entry.path) {
+        Some(existing) => *existing = entry,
+        None => {
+            index.push(entry);
+            index.sort_by(|a, b| a.path.cmp(&b.path));
+        }
+    }
+}
+
+fn repo_relative(repo: &Path, path: &Path) -> Result<String> {
     let p = fs::canonicalize(path).with_context(|| format!("Can't canonicalize {:?}", path))?;
     if !p.exists() {
         bail!("File {:?} does not exist", path);
@@ -138,15 +230,275 @@ fn cmd_add(path: &Path) -> Result<()> {
     let repo_abs = fs::canonicalize(repo)?;
     let rel = pathdiff::diff_paths(&p, &repo_abs)
         .ok_or_else(|| anyhow::anyhow!("Path not inside repo"))?;
-    let rel_str = rel.to_str().context("invalid utf8 path")?.to_string();
+    Ok(rel.to_str().context("invalid utf8 path")?.to_string())
+}
+
+fn cmd_add(path: &Path) -> Result<()> {
+    let repo_root = repo_root()?;
+    let repo = repo_root.as_path();
+    let rel_str = repo_relative(repo, path)?;
+    add_file(repo, &rel_str)?;
+    println!("Added {}", rel_str);
+    Ok(())
+}
+
+/// Stage the whole of `rel_str`, to be snapshotted at commit time.
+fn add_file(repo: &Path, rel_str: &str) -> Result<()> {
+    let mut index = load_index(repo)?;
+    stage_entry(
+        &mut index,
+        IndexEntry {
+            path: rel_str.to_string(),
+            blob: None,
+        },
+    );
+    write_index(repo, &index)
+}
+
+const HUNK_CONTEXT: usize = 3;
+
+#[derive(Clone, Copy)]
+enum HunkLine<'a> {
+    Context(&'a str),
+    Removed(&'a str),
+    Added(&'a str),
+}
+
+/// A run of consecutive non-context lines in the diff, as `[start, end)`
+/// indices into the line list. This is the smallest unit `add -p` can stage.
+struct Change {
+    start: usize,
+    end: usize,
+}
+
+/// Diff of the staged version against the working file, with every
+/// change group accepted or rejected individually.
+struct PatchSession<'a> {
+    lines: Vec<HunkLine<'a>>,
+    changes: Vec<Change>,
+    accepted: Vec<bool>,
+}
+
+impl<'a> PatchSession<'a> {
+    fn new(old: &'a str, new: &'a str) -> Self {
+        let old_lines: Vec<&str> = old.split_inclusive('\n').collect();
+        let new_lines: Vec<&str> = new.split_inclusive('\n').collect();
+        let lines: Vec<HunkLine> = diff::slice(&old_lines, &new_lines)
+            .into_iter()
+            .map(|d| match d {
+                diff::Result::Both(l, _) => HunkLine::Context(l),
+                diff::Result::Left(l) => HunkLine::Removed(l),
+                diff::Result::Right(r) => HunkLine::Added(r),
+            })
+            .collect();
+
+        let mut changes = vec![];
+        let mut i = 0;
+        while i < lines.len() {
+            if let HunkLine::Context(_) = lines[i] {
+                i += 1;
+                continue;
+            }
+            let start = i;
+            while i < lines.len() && !matches!(lines[i], HunkLine::Context(_)) {
+                i += 1;
+            }
+            changes.push(Change { start, end: i });
+        }
+        let accepted = vec![false; changes.len()];
+        PatchSession {
+            lines,
+            changes,
+            accepted,
+        }
+    }
+
+    /// Group change indices into hunks: changes whose surrounding context
+    /// would overlap are shown together, as `diff -u` does.
+    fn hunks(&self) -> Vec<Vec<usize>> {
+        let mut hunks: Vec<Vec<usize>> = vec![];
+        for (ci, change) in self.changes.iter().enumerate() {
+            if let Some(last) = hunks.last_mut() {
+                let prev = &self.changes[*last.last().unwrap()];
+                if change.start - prev.end <= 2 * HUNK_CONTEXT {
+                    last.push(ci);
+                    continue;
+                }
+            }
+            hunks.push(vec![ci]);
+        }
+        hunks
+    }
+
+    fn print_hunk(&self, hunk: &[usize]) {
+        let first = &self.changes[hunk[0]];
+        let last = &self.changes[hunk[hunk.len() - 1]];
+        let from = first.start.saturating_sub(HUNK_CONTEXT);
+        let to = std::cmp::min(self.lines.len(), last.end + HUNK_CONTEXT);
+
+        let (mut old_start, mut new_start) = (1, 1);
+        for line in &self.lines[..from] {
+            match line {
+                HunkLine::Context(_) => {
+                    old_start += 1;
+                    new_start += 1;
+                }
+                HunkLine::Removed(_) => old_start += 1,
+                HunkLine::Added(_) => new_start += 1,
+            }
+        }
+        let (mut old_len, mut new_len) = (0, 0);
+        for line in &self.lines[from..to] {
+            match line {
+                HunkLine::Context(_) => {
+                    old_len += 1;
+                    new_len += 1;
+                }
+                HunkLine::Removed(_) => old_len += 1,
+                HunkLine::Added(_) => new_len += 1,
+            }
+        }
+        println!("@@ -{},{} +{},{} @@", old_start, old_len, new_start, new_len);
+        for line in &self.lines[from..to] {
+            let (marker, text) = match line {
+                HunkLine::Context(l) => (' ', l),
+                HunkLine::Removed(l) => ('-', l),
+                HunkLine::Added(l) => ('+', l),
+            };
+            println!("{}{}", marker, text.trim_end_matches(['\r', '\n']));
+        }
+    }
+
+    /// Rebuild the file from the old version plus the accepted changes.
+    fn synthesize(&self) -> String {
+        let mut out = String::new();
+        let mut ci = 0;
+        for (i, line) in self.lines.iter().enumerate() {
+            while ci < self.changes.len() && self.changes[ci].end <= i {
+                ci += 1;
+            }
+            let take_new = ci < self.changes.len() && self.accepted[ci];
+            match line {
+                HunkLine::Context(l) => out.push_str(l),
+                HunkLine::Removed(l) if !take_new => out.push_str(l),
+                HunkLine::Added(l) if take_new => out.push_str(l),
+                _ => {}
+            }
+        }
+        out
+    }
+
+    /// Show each hunk and read a y/n/s/q answer for it from `input`.
+    /// Running out of input is treated as `q`.
+    fn choose(&mut self, input: &mut impl BufRead) -> Result<()> {
+        let mut queue = self.hunks();
+        queue.reverse();
+        while let Some(hunk) = queue.pop() {
+            self.print_hunk(&hunk);
+            let splittable = hunk.len() > 1;
+            let prompt = if splittable {
+                "Stage this hunk [y,n,s,q]? "
+            } else {
+                "Stage this hunk [y,n,q]? "
+            };
+            loop {
+                match prompt_choice(input, prompt)?.as_str() {
+                    "y" => {
+                        for &ci in &hunk {
+                            self.accepted[ci] = true;
+                        }
+                    }
+                    "n" => {}
+                    "s" if splittable => {
+                        println!("Split into {} hunks.", hunk.len());
+                        for &ci in hunk.iter().rev() {
+                            queue.push(vec![ci]);
+                        }
+                    }
+                    "q" => queue.clear(),
+                    _ => {
+                        println!("y - stage this hunk");
+                        println!("n - do not stage this hunk");
+                        if splittable {
+                            println!("s - split this hunk into smaller hunks");
+                        }
+                        println!("q - quit; do not stage this or any remaining hunk");
+                        continue;
+                    }
+                }
+                break;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn prompt_choice(stdin: &mut impl BufRead, prompt: &str) -> Result<String> {
+    print!("{}", prompt);
+    std::io::stdout().flush()?;
+    let mut line = String::new();
+    if stdin.read_line(&mut line)? == 0 {
+        return Ok("q".to_string());
+    }
+    Ok(line.trim().to_lowercase())
+}
 
+fn cmd_add_patch(path: &Path) -> Result<()> {
+    let repo_root = repo_root()?;
+    let repo = repo_root.as_path();
+    let rel_str = repo_relative(repo, path)?;
+    let stdin = std::io::stdin();
+    add_patch(repo, &rel_str, &mut stdin.lock())
+}
+
+/// Offer each hunk of `rel_str` against what is staged (or HEAD) and stage
+/// the accepted ones. A path already staged whole is refused, since a
+/// partial blob would silently unstage the hunks left out.
+fn add_patch(repo: &Path, rel_str: &str, input: &mut impl BufRead) -> Result<()> {
     let mut index = load_index(repo)?;
-    if !index.contains(&rel_str) {
-        index.push(rel_str.clone());
-        index.sort();
+    let staged = index.iter().find(|e| e.path == rel_str);
+    if staged.is_some_and(|e| e.blob.is_none()) {
+        bail!("{} is already fully staged", rel_str);
     }
+    let base_blob = match staged.and_then(|e| e.blob.clone()) {
+        Some(b) => Some(b),
+        None => match read_head(repo)? {
+            Some(h) => load_commit(repo, &h)?.tree.get(rel_str).cloned(),
+            None => None,
+        },
+    };
+    let old_bytes = match base_blob {
+        Some(ref h) => load_blob(repo, h)?,
+        None => vec![],
+    };
+    let attrs = load_attributes(repo)?.for_path(rel_str);
+    let new_bytes = attrs.clean(&fs::read(repo.join(rel_str))?);
+    let old = String::from_utf8(old_bytes).context("cannot stage hunks of a binary file")?;
+    let new = String::from_utf8(new_bytes).context("cannot stage hunks of a binary file")?;
+
+    let mut session = PatchSession::new(&old, &new);
+    if session.changes.is_empty() {
+        println!("No changes in {}", rel_str);
+        return Ok(());
+    }
+
+    println!("diff {}", rel_str);
+    session.choose(input)?;
+
+    if !session.accepted.iter().any(|&a| a) {
+        println!("No changes staged.");
+        return Ok(());
+    }
+    let blob_hash = store_blob(repo, session.synthesize().as_bytes())?;
+    stage_entry(
+        &mut index,
+        IndexEntry {
+            path: rel_str.to_string(),
+            blob: Some(blob_hash),
+        },
+    );
     write_index(repo, &index)?;
-    println!("Added {}", rel_str);
+    println!("Staged selected hunks of {}", rel_str);
     Ok(())
 }
 
@@ -181,12 +533,46 @@ fn read_head(repo: &Path) -> Result<Option<String>> {
     }
 }
 
-fn write_head(repo: &Path, hash: &str) -> Result<()> {
+/// Point HEAD at `hash` and append the move to the reflog.
+fn write_head(repo: &Path, hash: &str, command: &str) -> Result<()> {
+    let old = read_head(repo)?;
     let p = repo.join(HEAD_FILE);
     fs::write(p, hash)?;
+    append_reflog(
+        repo,
+        &ReflogEntry {
+            old,
+            new: hash.to_string(),
+            timestamp: Utc::now(),
+            command: command.to_string(),
+        },
+    )
+}
+
+fn append_reflog(repo: &Path, entry: &ReflogEntry) -> Result<()> {
+    fs::create_dir_all(repo.join(LOGS_DIR))?;
+    let mut f = fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(repo.join(HEAD_LOG_FILE))?;
+    writeln!(f, "{}", serde_json::to_string(entry)?)?;
     Ok(())
 }
 
+fn load_reflog(repo: &Path) -> Result<Vec<ReflogEntry>> {
+    let p = repo.join(HEAD_LOG_FILE);
+    if !p.exists() {
+        return Ok(vec![]);
+    }
+    let mut out = vec![];
+    for line in fs::read_to_string(p)?.lines() {
+        if !line.trim().is_empty() {
+            out.push(serde_json::from_str(line)?);
+        }
+    }
+    Ok(out)
+}
+
 fn cmd_commit(message: &str) -> Result<()> {
     let repo_root = repo_root()?;
     let repo = repo_root.as_path();
@@ -194,8 +580,14 @@ fn cmd_commit(message: &str) -> Result<()> {
     if index.is_empty() {
         bail!("Nothing staged. Use `scm add <file>` to stage files.");
     }
+    let attributes = load_attributes(repo)?;
     let mut tree = BTreeMap::new();
-    for rel in &index {
+    for entry in &index {
+        let rel = &entry.path;
+        if let Some(ref blob_hash) = entry.blob {
+            tree.insert(rel.clone(), blob_hash.clone());
+            continue;
+        }
         let file_path = repo.join(rel);
         if !file_path.exists() {
             bail!("Staged file missing: {}", rel);
@@ -203,7 +595,7 @@ fn cmd_commit(message: &str) -> Result<()> {
         let mut f = fs::File::open(&file_path)?;
         let mut bytes = vec![];
         f.read_to_end(&mut bytes)?;
-        let blob_hash = store_blob(repo, &bytes)?;
+        let blob_hash = store_blob(repo, &attributes.for_path(rel).clean(&bytes))?;
         tree.insert(rel.clone(), blob_hash);
     }
 
@@ -214,16 +606,21 @@ fn cmd_commit(message: &str) -> Result<()> {
         message: message.to_string(),
         timestamp: Utc::now(),
     };
-    let commit_hash = commit_object_hash(&commit)?;
-    let commit_path = repo.join(COMMITS_DIR).join(&commit_hash);
-    let commit_json = serde_json::to_vec_pretty(&commit)?;
-    fs::write(&commit_path, &commit_json)?;
-    write_head(repo, &commit_hash)?;
+    let commit_hash = store_commit(repo, &commit)?;
+    write_head(repo, &commit_hash, &format!("commit: {}", message))?;
     write_index(repo, &[])?;
     println!("Committed: {}", commit_hash);
     Ok(())
 }
 
+fn store_commit(repo: &Path, commit: &Commit) -> Result<String> {
+    let commit_hash = commit_object_hash(commit)?;
+    let commit_path = repo.join(COMMITS_DIR).join(&commit_hash);
+    let commit_json = serde_json::to_vec_pretty(commit)?;
+    fs::write(&commit_path, &commit_json)?;
+    Ok(commit_hash)
+}
+
 fn load_commit(repo: &Path, hash: &str) -> Result<Commit> {
     let path = repo.join(COMMITS_DIR).join(hash);
     if !path.exists() {
@@ -236,6 +633,7 @@ fn load_commit(repo: &Path, hash: &str) -> Result<Commit> {
 
 fn checkout_commit(repo: &Path, commit_hash: &str) -> Result<()> {
     let commit = load_commit(repo, commit_hash)?;
+    let attributes = load_attributes(repo)?;
     for (rel, blob_hash) in commit.tree.iter() {
         let blob_path = repo.join(OBJECTS_DIR).join(blob_hash);
         if !blob_path.exists() {
@@ -246,7 +644,7 @@ fn checkout_commit(repo: &Path, commit_hash: &str) -> Result<()> {
         if let Some(parent) = target.parent() {
             fs::create_dir_all(parent)?;
         }
-        fs::write(&target, &bytes)?;
+        fs::write(&target, attributes.for_path(rel).smudge(&bytes))?;
     }
     Ok(())
 }
@@ -266,7 +664,7 @@ fn cmd_revert() -> Result<()> {
     };
     let _parent_commit = load_commit(repo, &parent)?;
     checkout_commit(repo, &parent)?;
-    write_head(repo, &parent)?;
+    write_head(repo, &parent, &format!("revert: {}", commit.message))?;
     println!("Reverted HEAD {} -> {}", head, parent);
     Ok(())
 }
@@ -283,37 +681,753 @@ fn cmd_log() -> Result<()> {
     Ok(())
 }
 
-fn file_content_hash(repo: &Path, rel: &str) -> Result<Option<String>> {
+/// A snapshot directory to import, with the timestamp and message its
+/// commit will carry.
+struct Snapshot {
+    dir: PathBuf,
+    timestamp: DateTime<Utc>,
+    message: String,
+}
+
+fn parse_snapshot_date(s: &str) -> Result<DateTime<Utc>> {
+    if let Ok(t) = DateTime::parse_from_rfc3339(s) {
+        return Ok(t.with_timezone(&Utc));
+    }
+    let d = chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+        .with_context(|| format!("bad snapshot date {:?}", s))?;
+    Ok(d.and_hms_opt(0, 0, 0).unwrap().and_utc())
+}
+
+fn default_snapshot_message(dir: &Path) -> String {
+    let name = dir.file_name().unwrap_or(dir.as_os_str());
+    format!("Import snapshot {}", name.to_string_lossy())
+}
+
+/// Read a manifest; relative directories are resolved against the
+/// manifest's own location.
+fn load_manifest(path: &Path) -> Result<Vec<Snapshot>> {
+    let base = path.parent().unwrap_or(Path::new("."));
+    let s = fs::read_to_string(path).with_context(|| format!("Can't read manifest {:?}", path))?;
+    let mut out = vec![];
+    for (n, line) in s.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        // Columns may be padded with any run of spaces or tabs; the message
+        // is whatever follows the date.
+        let (dir, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+        let rest = rest.trim_start();
+        let (date, message) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+        if date.is_empty() {
+            bail!("manifest line {}: missing date", n + 1);
+        }
+        let dir = base.join(dir);
+        let message = match message.trim() {
+            "" => default_snapshot_message(&dir),
+            m => m.to_string(),
+        };
+        out.push(Snapshot {
+            timestamp: parse_snapshot_date(date)?,
+            dir,
+            message,
+        });
+    }
+    Ok(out)
+}
+
+/// Collect every file under `dir` into a tree keyed by its path relative
+/// to `root`, storing each blob on the way.
+fn snapshot_tree(
+    repo: &Path,
+    attributes: &Attributes,
+    root: &Path,
+    dir: &Path,
+    tree: &mut BTreeMap<String, String>,
+) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_name() == SCM_DIR {
+            continue;
+        }
+        if entry.file_type()?.is_dir() {
+            snapshot_tree(repo, attributes, root, &path, tree)?;
+            continue;
+        }
+        let rel = pathdiff::diff_paths(&path, root)
+            .ok_or_else(|| anyhow::anyhow!("Path not inside snapshot"))?;
+        let rel = rel.to_str().context("invalid utf8 path")?.replace('\\', "/");
+        let bytes = attributes.for_path(&rel).clean(&fs::read(&path)?);
+        let blob_hash = store_blob(repo, &bytes)?;
+        tree.insert(rel, blob_hash);
+    }
+    Ok(())
+}
+
+fn cmd_import_snapshots(dirs: &[PathBuf], manifest: Option<PathBuf>) -> Result<()> {
+    let repo_root = repo_root()?;
+    let repo = repo_root.as_path();
+    if manifest.is_some() && !dirs.is_empty() {
+        bail!("Pass snapshot directories either on the command line or in --manifest, not both.");
+    }
+    let snapshots = match manifest {
+        Some(ref m) => load_manifest(m)?,
+        None => {
+            let mut out = vec![];
+            for dir in dirs {
+                let mtime = fs::metadata(dir)
+                    .with_context(|| format!("Can't read snapshot {:?}", dir))?
+                    .modified()?;
+                out.push(Snapshot {
+                    dir: dir.clone(),
+                    timestamp: mtime.into(),
+                    message: default_snapshot_message(dir),
+                });
+            }
+            out
+        }
+    };
+    if snapshots.is_empty() {
+        bail!("No snapshot directories given.");
+    }
+
+    for (snap, hash) in snapshots.iter().zip(import_snapshots(repo, &snapshots)?) {
+        println!("Imported {} as {}", snap.dir.display(), hash);
+    }
+    Ok(())
+}
+
+/// Commit each snapshot on top of HEAD in order, then check out the last
+/// one. Refuses, before writing any commit, if there are staged changes,
+/// if tracked files differ from HEAD, or if an untracked file would be
+/// overwritten. Returns the new commit hashes.
+fn import_snapshots(repo: &Path, snapshots: &[Snapshot]) -> Result<Vec<String>> {
+    let attributes = load_attributes(repo)?;
+    let head = read_head(repo)?;
+    let head_tree = match head {
+        Some(ref h) => load_commit(repo, h)?.tree,
+        None => BTreeMap::new(),
+    };
+    if !load_index(repo)?.is_empty() {
+        bail!("Cannot import snapshots with staged changes; commit them first.");
+    }
+    for (path, blob_hash) in &head_tree {
+        if file_content_hash(repo, &attributes, path)?.as_ref() != Some(blob_hash) {
+            bail!("Cannot import snapshots: {} has local changes.", path);
+        }
+    }
+
+    let mut trees = vec![];
+    for snap in snapshots {
+        if !snap.dir.is_dir() {
+            bail!("Snapshot {:?} is not a directory", snap.dir);
+        }
+        let mut tree = BTreeMap::new();
+        snapshot_tree(repo, &attributes, &snap.dir, &snap.dir, &mut tree)?;
+        trees.push(tree);
+    }
+    let final_tree = trees.last().cloned().unwrap_or_default();
+    for (path, blob_hash) in &final_tree {
+        if head_tree.contains_key(path) {
+            continue;
+        }
+        match file_content_hash(repo, &attributes, path)? {
+            Some(ref h) if h != blob_hash => {
+                bail!("Cannot import snapshots: untracked {} would be overwritten.", path)
+            }
+            _ => {}
+        }
+    }
+
+    let mut parent = head;
+    let mut hashes = vec![];
+    for (snap, tree) in snapshots.iter().zip(trees) {
+        let commit = Commit {
+            tree,
+            parent: parent.clone(),
+            message: snap.message.clone(),
+            timestamp: snap.timestamp,
+        };
+        let commit_hash = store_commit(repo, &commit)?;
+        write_head(repo, &commit_hash, &format!("import-snapshots: {}", snap.message))?;
+        parent = Some(commit_hash.clone());
+        hashes.push(commit_hash);
+    }
+    if let Some(last) = hashes.last() {
+        checkout_commit(repo, last)?;
+        for path in head_tree.keys() {
+            if !final_tree.contains_key(path) {
+                fs::remove_file(repo.join(path))?;
+            }
+        }
+    }
+    Ok(hashes)
+}
+
+fn cmd_reflog() -> Result<()> {
+    let repo_root = repo_root()?;
+    let repo = repo_root.as_path();
+    let entries = load_reflog(repo)?;
+    for (i, e) in entries.iter().rev().enumerate() {
+        println!(
+            "{} HEAD@{{{}}}: {} ({})",
+            &e.new[..std::cmp::min(12, e.new.len())],
+            i,
+            e.command,
+            e.timestamp.to_rfc3339()
+        );
+    }
+    Ok(())
+}
+
+/// Parse a `--prune` age. `None` means never expire.
+fn parse_prune_age(s: &str) -> Result<Option<chrono::Duration>> {
+    let s = s.trim();
+    match s {
+        "never" => return Ok(None),
+        "now" | "all" => return Ok(Some(chrono::Duration::zero())),
+        _ => {}
+    }
+    let split = s.find(|c: char| !c.is_ascii_digit()).unwrap_or(s.len());
+    let (num, unit) = s.split_at(split);
+    let n: i64 = num
+        .parse()
+        .with_context(|| format!("bad prune age {:?}", s))?;
+    let d = match unit.trim_start_matches('.') {
+        "s" | "sec" | "second" | "seconds" => chrono::Duration::try_seconds(n),
+        "m" | "min" | "minute" | "minutes" => chrono::Duration::try_minutes(n),
+        "h" | "hour" | "hours" => chrono::Duration::try_hours(n),
+        "" | "d" | "day" | "days" => chrono::Duration::try_days(n),
+        "w" | "week" | "weeks" => chrono::Duration::try_weeks(n),
+        _ => bail!("bad prune age {:?}", s),
+    };
+    d.map(Some).ok_or_else(|| anyhow::anyhow!("prune age out of range: {:?}", s))
+}
+
+/// The moment `age` before `now`, or an error if that is before the
+/// earliest representable time.
+fn prune_cutoff(now: DateTime<Utc>, age: chrono::Duration) -> Result<DateTime<Utc>> {
+    now.checked_sub_signed(age)
+        .ok_or_else(|| anyhow::anyhow!("prune age out of range"))
+}
+
+/// Mark `hash` and its ancestors, plus every blob they reference.
+fn mark_reachable(
+    repo: &Path,
+    hash: &str,
+    commits: &mut BTreeSet<String>,
+    blobs: &mut BTreeSet<String>,
+) -> Result<()> {
+    let mut cur = Some(hash.to_string());
+    while let Some(h) = cur {
+        if !commits.insert(h.clone()) || !repo.join(COMMITS_DIR).join(&h).exists() {
+            break;
+        }
+        let c = load_commit(repo, &h)?;
+        blobs.extend(c.tree.values().cloned());
+        cur = c.parent;
+    }
+    Ok(())
+}
+
+/// Remove every file in `dir` not in `keep` and last modified before `cutoff`.
+fn prune_dir(dir: &Path, keep: &BTreeSet<String>, cutoff: DateTime<Utc>) -> Result<usize> {
+    let mut removed = 0;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().to_string();
+        if keep.contains(&name) {
+            continue;
+        }
+        let mtime: DateTime<Utc> = entry.metadata()?.modified()?.into();
+        if mtime <= cutoff {
+            fs::remove_file(entry.path())?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+fn cmd_gc(prune: &str) -> Result<()> {
+    let repo_root = repo_root()?;
+    let repo = repo_root.as_path();
+    let age = match parse_prune_age(prune)? {
+        Some(age) => age,
+        None => {
+            println!("Nothing to prune (--prune=never).");
+            return Ok(());
+        }
+    };
+    let report = gc(repo, prune_cutoff(Utc::now(), age)?)?;
+    println!(
+        "Removed {} commits and {} objects; expired {} reflog entries.",
+        report.commits, report.blobs, report.reflog_entries
+    );
+    Ok(())
+}
+
+/// What a `gc` run removed.
+#[derive(Debug, PartialEq)]
+struct GcReport {
+    commits: usize,
+    blobs: usize,
+    reflog_entries: usize,
+}
+
+/// Expire reflog entries from before `cutoff`, then delete commits and
+/// objects older than `cutoff` that are unreachable from HEAD, the index
+/// and the remaining reflog entries.
+fn gc(repo: &Path, cutoff: DateTime<Utc>) -> Result<GcReport> {
+    let mut commits = BTreeSet::new();
+    let mut blobs = BTreeSet::new();
+    if let Some(h) = read_head(repo)? {
+        mark_reachable(repo, &h, &mut commits, &mut blobs)?;
+    }
+    for e in load_index(repo)? {
+        if let Some(b) = e.blob {
+            blobs.insert(b);
+        }
+    }
+    let (recent, expired): (Vec<ReflogEntry>, Vec<ReflogEntry>) = load_reflog(repo)?
+        .into_iter()
+        .partition(|e| e.timestamp > cutoff);
+    for e in &recent {
+        mark_reachable(repo, &e.new, &mut commits, &mut blobs)?;
+        if let Some(ref old) = e.old {
+            mark_reachable(repo, old, &mut commits, &mut blobs)?;
+        }
+    }
+
+    let removed_commits = prune_dir(&repo.join(COMMITS_DIR), &commits, cutoff)?;
+    let removed_blobs = prune_dir(&repo.join(OBJECTS_DIR), &blobs, cutoff)?;
+    if !expired.is_empty() {
+        let mut s = String::new();
+        for e in &recent {
+            s.push_str(&serde_json::to_string(e)?);
+            s.push('\n');
+        }
+        fs::write(repo.join(HEAD_LOG_FILE), s)?;
+    }
+    Ok(GcReport {
+        commits: removed_commits,
+        blobs: removed_blobs,
+        reflog_entries: expired.len(),
+    })
+}
+
+/// Hash of the working file as it would be stored, i.e. after normalization.
+fn file_content_hash(repo: &Path, attributes: &Attributes, rel: &str) -> Result<Option<String>> {
     let p = repo.join(rel);
     if !p.exists() {
         return Ok(None);
     }
-    let b = fs::read(&p)?;
+    let b = attributes.for_path(rel).clean(&fs::read(&p)?);
     Ok(Some(hash_bytes(&b)))
 }
 
-fn cmd_status() -> Result<()> {
+/// A single `.scmignore` line. Patterns follow the usual gitignore rules:
+/// `#` starts a comment, `!` re-includes, a trailing `/` only matches
+/// directories, and a pattern containing `/` is anchored at the repo root.
+struct IgnoreRule {
+    pattern: String,
+    negate: bool,
+    dir_only: bool,
+    anchored: bool,
+}
+
+fn load_ignore_rules(repo: &Path) -> Result<Vec<IgnoreRule>> {
+    let p = repo.join(IGNORE_FILE);
+    if !p.exists() {
+        return Ok(vec![]);
+    }
+    Ok(parse_ignore_rules(&fs::read_to_string(p)?))
+}
+
+fn parse_ignore_rules(text: &str) -> Vec<IgnoreRule> {
+    let mut rules = vec![];
+    for line in text.lines() {
+        let mut pat = line.trim();
+        if pat.is_empty() || pat.starts_with('#') {
+            continue;
+        }
+        let negate = pat.starts_with('!');
+        if negate {
+            pat = &pat[1..];
+        }
+        let dir_only = pat.ends_with('/');
+        let pat = pat.trim_end_matches('/');
+        let anchored = pat.contains('/');
+        rules.push(IgnoreRule {
+            pattern: pat.trim_start_matches('/').to_string(),
+            negate,
+            dir_only,
+            anchored,
+        });
+    }
+    rules
+}
+
+/// Glob match where `*` and `?` stop at `/`. A `**` that makes up a whole
+/// path segment crosses directories: `**/x` matches `x` at any depth,
+/// `x/**` everything inside `x`, and `a/**/b` zero or more directories
+/// between `a` and `b`. Anywhere else `**` acts like `*`.
+fn glob_match(pat: &[u8], text: &[u8]) -> bool {
+    glob_match_from(pat, text, true)
+}
+
+/// `segment_start` says whether `pat` begins right after a `/` or at the
+/// start of the whole pattern.
+fn glob_match_from(pat: &[u8], text: &[u8], segment_start: bool) -> bool {
+    match pat {
+        [] => text.is_empty(),
+        [b'*', b'*'] if segment_start => true,
+        [b'*', b'*', b'/', rest @ ..] if segment_start => {
+            glob_match_from(rest, text, true)
+                || text
+                    .iter()
+                    .enumerate()
+                    .any(|(i, &c)| c == b'/' && glob_match_from(rest, &text[i + 1..], true))
+        }
+        [b'*', rest @ ..] => {
+            for i in 0..=text.len() {
+                if glob_match_from(rest, &text[i..], false) {
+                    return true;
+                }
+                if i < text.len() && text[i] == b'/' {
+                    break;
+                }
+            }
+            false
+        }
+        [b'?', rest @ ..] => {
+            !text.is_empty() && text[0] != b'/' && glob_match_from(rest, &text[1..], false)
+        }
+        [c, rest @ ..] => {
+            !text.is_empty() && text[0] == *c && glob_match_from(rest, &text[1..], *c == b'/')
+        }
+    }
+}
+
+fn is_ignored(rules: &[IgnoreRule], rel: &str, is_dir: bool) -> bool {
+    let name = rel.rsplit('/').next().unwrap_or(rel);
+    let mut ignored = false;
+    for rule in rules {
+        if rule.dir_only && !is_dir {
+            continue;
+        }
+        let subject = if rule.anchored { rel } else { name };
+        if glob_match(rule.pattern.as_bytes(), subject.as_bytes()) {
+            ignored = !rule.negate;
+        }
+    }
+    ignored
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum TextAttr {
+    Unspecified,
+    Text,
+    Binary,
+    Auto,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Eol {
+    Lf,
+    Crlf,
+}
+
+/// Attributes resolved for one path from `.scmattributes`.
+#[derive(Clone, Copy, Debug)]
+struct PathAttrs {
+    text: TextAttr,
+    eol: Option<Eol>,
+    fix_whitespace: bool,
+}
+
+impl PathAttrs {
+    fn is_text(&self, bytes: &[u8]) -> bool {
+        match self.text {
+            TextAttr::Text => true,
+            TextAttr::Binary => false,
+            TextAttr::Auto => !bytes[..std::cmp::min(8000, bytes.len())].contains(&0),
+            // Setting `eol` alone marks the path as text.
+            TextAttr::Unspecified => self.eol.is_some(),
+        }
+    }
+
+    /// Working copy -> repository: LF line endings and, with
+    /// `whitespace=fix`, no trailing blanks.
+    fn clean(&self, bytes: &[u8]) -> Vec<u8> {
+        if !self.is_text(bytes) {
+            return bytes.to_vec();
+        }
+        let mut out = Vec::with_capacity(bytes.len());
+        for line in bytes.split_inclusive(|&b| b == b'\n') {
+            let (body, newline) = match line.strip_suffix(b"\n") {
+                Some(body) => (body.strip_suffix(b"\r").unwrap_or(body), true),
+                None => (line, false),
+            };
+            let body = if self.fix_whitespace {
+                let end = body
+                    .iter()
+                    .rposition(|&b| b != b' ' && b != b'\t')
+                    .map_or(0, |i| i + 1);
+                &body[..end]
+            } else {
+                body
+            };
+            out.extend_from_slice(body);
+            if newline {
+                out.push(b'\n');
+            }
+        }
+        out
+    }
+
+    /// Repository -> working copy: apply `eol=crlf` on checkout.
+    fn smudge(&self, bytes: &[u8]) -> Vec<u8> {
+        if self.eol != Some(Eol::Crlf) || !self.is_text(bytes) {
+            return bytes.to_vec();
+        }
+        let mut out = Vec::with_capacity(bytes.len() + bytes.len() / 32);
+        for (i, &b) in bytes.iter().enumerate() {
+            // A CRLF already in the blob is left alone.
+            if b == b'\n' && (i == 0 || bytes[i - 1] != b'\r') {
+                out.push(b'\r');
+            }
+            out.push(b);
+        }
+        out
+    }
+}
+
+/// A `.scmattributes` line: a path pattern (matched like `.scmignore`)
+/// followed by attributes such as `text=auto`, `-text`, `eol=crlf` or
+/// `whitespace=fix`. Later lines override earlier ones.
+struct AttrRule {
+    pattern: String,
+    anchored: bool,
+    attrs: Vec<String>,
+}
+
+struct Attributes {
+    rules: Vec<AttrRule>,
+}
+
+fn load_attributes(repo: &Path) -> Result<Attributes> {
+    let p = repo.join(ATTRIBUTES_FILE);
+    if !p.exists() {
+        return Ok(parse_attributes(""));
+    }
+    Ok(parse_attributes(&fs::read_to_string(p)?))
+}
+
+fn parse_attributes(text: &str) -> Attributes {
+    let mut rules = vec![];
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut parts = line.split_whitespace();
+        let pat = parts.next().unwrap();
+        rules.push(AttrRule {
+            pattern: pat.trim_start_matches('/').to_string(),
+            anchored: pat.contains('/'),
+            attrs: parts.map(|a| a.to_string()).collect(),
+        });
+    }
+    Attributes { rules }
+}
+
+impl Attributes {
+    fn for_path(&self, rel: &str) -> PathAttrs {
+        let name = rel.rsplit('/').next().unwrap_or(rel);
+        let mut out = PathAttrs {
+            text: TextAttr::Unspecified,
+            eol: None,
+            fix_whitespace: false,
+        };
+        for rule in &self.rules {
+            let subject = if rule.anchored { rel } else { name };
+            if !glob_match(rule.pattern.as_bytes(), subject.as_bytes()) {
+                continue;
+            }
+            for attr in &rule.attrs {
+                match attr.as_str() {
+                    "text" => out.text = TextAttr::Text,
+                    "-text" | "binary" => out.text = TextAttr::Binary,
+                    "text=auto" => out.text = TextAttr::Auto,
+                    "eol=lf" => out.eol = Some(Eol::Lf),
+                    "eol=crlf" => out.eol = Some(Eol::Crlf),
+                    "whitespace=fix" => out.fix_whitespace = true,
+                    "-whitespace" | "whitespace=keep" => out.fix_whitespace = false,
+                    _ => {}
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Walk the working directory, splitting paths that are neither in HEAD nor
+/// staged into untracked files and ignored paths. Ignored directories are
+/// reported once with a trailing `/` and not descended into.
+fn walk_untracked(
+    repo: &Path,
+    dir: &Path,
+    rules: &[IgnoreRule],
+    tracked: &BTreeSet<String>,
+    untracked: &mut Vec<String>,
+    ignored: &mut Vec<String>,
+) -> Result<()> {
+    let mut entries = fs::read_dir(dir)?.collect::<std::io::Result<Vec<_>>>()?;
+    entries.sort_by_key(|e| e.file_name());
+    for entry in entries {
+        let path = entry.path();
+        let rel = pathdiff::diff_paths(&path, repo)
+            .ok_or_else(|| anyhow::anyhow!("Path not inside repo"))?;
+        let rel = rel.to_str().context("invalid utf8 path")?.replace('\\', "/");
+        if rel == SCM_DIR {
+            continue;
+        }
+        let is_dir = entry.file_type()?.is_dir();
+        if tracked.contains(&rel) {
+            continue;
+        }
+        if is_ignored(rules, &rel, is_dir) {
+            if is_dir {
+                ignored.push(format!("{}/", rel));
+            } else {
+                ignored.push(rel);
+            }
+        } else if is_dir {
+            walk_untracked(repo, &path, rules, tracked, untracked, ignored)?;
+        } else {
+            untracked.push(rel);
+        }
+    }
+    Ok(())
+}
+
+/// The two-letter `status --short` codes for staged and tracked paths: X is
+/// the index against HEAD, Y is the working copy against the index. Paths
+/// that are unchanged in both are left out.
+fn short_status_codes(
+    repo: &Path,
+    attributes: &Attributes,
+    staged: &[IndexEntry],
+    tree: &BTreeMap<String, String>,
+) -> Result<BTreeMap<String, (char, char)>> {
+    let mut lines = BTreeMap::new();
+    for e in staged {
+        let cur = file_content_hash(repo, attributes, &e.path)?;
+        // A whole-file stage is whatever the working copy holds, so a missing
+        // file is a staged deletion rather than a modification.
+        let (x, y) = match (&e.blob, &cur, tree.get(&e.path)) {
+            (None, None, Some(_)) => ('D', ' '),
+            (None, None, None) => ('A', 'D'),
+            (blob, _, head) => {
+                let staged_hash = blob.as_ref().or(cur.as_ref());
+                let x = match head {
+                    None => 'A',
+                    Some(h) if staged_hash == Some(h) => ' ',
+                    Some(_) => 'M',
+                };
+                let y = match cur {
+                    None => 'D',
+                    Some(ref c) if staged_hash != Some(c) => 'M',
+                    Some(_) => ' ',
+                };
+                (x, y)
+            }
+        };
+        lines.insert(e.path.clone(), (x, y));
+    }
+    for (path, blob_hash) in tree.iter() {
+        if lines.contains_key(path) {
+            continue;
+        }
+        match file_content_hash(repo, attributes, path)? {
+            None => lines.insert(path.clone(), (' ', 'D')),
+            Some(h) if h != *blob_hash => lines.insert(path.clone(), (' ', 'M')),
+            Some(_) => None,
+        };
+    }
+    lines.retain(|_, &mut code| code != (' ', ' '));
+    Ok(lines)
+}
+
+fn cmd_status(short: bool, show_ignored: bool) -> Result<()> {
     let repo_root = repo_root()?;
     let repo = repo_root.as_path();
     let staged = load_index(repo)?;
-    println!("Staged files:");
-    for s in &staged {
-        println!("  {}", s);
+    let head_tree = match read_head(repo)? {
+        Some(h) => Some(load_commit(repo, &h)?.tree),
+        None => None,
+    };
+
+    let mut tracked: BTreeSet<String> = staged.iter().map(|e| e.path.clone()).collect();
+    if let Some(ref tree) = head_tree {
+        tracked.extend(tree.keys().cloned());
     }
-    let head = read_head(repo)?;
-    if head.is_none() {
-        println!("No commits yet.");
+    let rules = load_ignore_rules(repo)?;
+    let attributes = load_attributes(repo)?;
+    let mut untracked = vec![];
+    let mut ignored = vec![];
+    walk_untracked(repo, repo, &rules, &tracked, &mut untracked, &mut ignored)?;
+
+    if short {
+        let empty = BTreeMap::new();
+        let tree = head_tree.as_ref().unwrap_or(&empty);
+        let mut lines = short_status_codes(repo, &attributes, &staged, tree)?;
+        for path in &untracked {
+            lines.insert(path.clone(), ('?', '?'));
+        }
+        if show_ignored {
+            for path in &ignored {
+                lines.insert(path.clone(), ('!', '!'));
+            }
+        }
+        for (path, (x, y)) in lines {
+            println!("{}{} {}", x, y, path);
+        }
         return Ok(());
     }
-    let head = head.unwrap();
-    let commit = load_commit(repo, &head)?;
-    println!("\nTracked files (in HEAD):");
-    for (path, blob_hash) in commit.tree.iter() {
-        let cur_hash = file_content_hash(repo, path)?;
-        match cur_hash {
-            None => println!("  {} (deleted)", path),
-            Some(h) if h == *blob_hash => println!("  {} (up-to-date)", path),
-            Some(_) => println!("  {} (modified)", path),
+
+    println!("Staged files:");
+    for e in &staged {
+        match e.blob {
+            Some(_) => println!("  {} (partial)", e.path),
+            None => println!("  {}", e.path),
+        }
+    }
+    match head_tree {
+        None => println!("No commits yet."),
+        Some(ref tree) => {
+            println!("\nTracked files (in HEAD):");
+            for (path, blob_hash) in tree.iter() {
+                let cur_hash = file_content_hash(repo, &attributes, path)?;
+                match cur_hash {
+                    None => println!("  {} (deleted)", path),
+                    Some(h) if h == *blob_hash => println!("  {} (up-to-date)", path),
+                    Some(_) => println!("  {} (modified)", path),
+                }
+            }
+        }
+    }
+    if !untracked.is_empty() {
+        println!("\nUntracked files:");
+        for path in &untracked {
+            println!("  {}", path);
+        }
+    }
+    if show_ignored && !ignored.is_empty() {
+        println!("\nIgnored files:");
+        for path in &ignored {
+            println!("  {}", path);
         }
     }
     Ok(())
@@ -328,15 +1442,17 @@ fn load_blob(repo: &Path, hash: &str) -> Result<Vec<u8>> {
     Ok(b)
 }
 
-fn read_file_to_lines(path: &Path) -> Result<Vec<String>> {
+fn read_file_to_lines(repo: &Path, attributes: &Attributes, rel: &str) -> Result<Vec<String>> {
+    let path = repo.join(rel);
     if !path.exists() {
         return Ok(vec![]);
     }
-    let s = fs::read_to_string(path)?;
+    let bytes = attributes.for_path(rel).clean(&fs::read(path)?);
+    let s = String::from_utf8(bytes)?;
     Ok(s.lines().map(|l| l.to_string()).collect::<Vec<String>>())
 }
 
-fn cmd_diff(file: Option<PathBuf>) -> Result<()> {
+fn cmd_diff(file: Option<PathBuf>, ignore_space: bool) -> Result<()> {
     let repo_root = repo_root()?;
     let repo = repo_root.as_path();
     let head = read_head(repo)?;
@@ -346,6 +1462,7 @@ fn cmd_diff(file: Option<PathBuf>) -> Result<()> {
     }
     let head = head.unwrap();
     let commit = load_commit(repo, &head)?;
+    let attributes = load_attributes(repo)?;
 
     if let Some(fp) = file {
         let rel = pathdiff::diff_paths(&fs::canonicalize(fp)?, &fs::canonicalize(repo)?)
@@ -361,8 +1478,8 @@ fn cmd_diff(file: Option<PathBuf>) -> Result<()> {
         } else {
             vec![]
         };
-        let working = read_file_to_lines(&repo.join(rels))?;
-        print_diff(&committed_lines, &working, rels);
+        let working = read_file_to_lines(repo, &attributes, rels)?;
+        print_diff(&committed_lines, &working, rels, ignore_space);
     } else {
         for (path, hash) in commit.tree.iter() {
             let committed_lines: Vec<String> = {
@@ -372,21 +1489,61 @@ fn cmd_diff(file: Option<PathBuf>) -> Result<()> {
                     .map(|s| s.to_string())
                     .collect::<Vec<String>>()
             };
-            let working = read_file_to_lines(&repo.join(path))?;
-            if committed_lines != working {
-                print_diff(&committed_lines, &working, path);
+            let working = read_file_to_lines(repo, &attributes, path)?;
+            if !lines_equal(&committed_lines, &working, ignore_space) {
+                print_diff(&committed_lines, &working, path, ignore_space);
             }
         }
     }
     Ok(())
 }
 
-fn print_diff(a: &[String], b: &[String], label: &str) {
-    use diff::lines;
+/// A line compared with all whitespace removed, for `diff -w`.
+struct SpaceInsensitive<'a>(&'a str);
+
+impl PartialEq for SpaceInsensitive<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        let a = self.0.chars().filter(|c| !c.is_whitespace());
+        let b = other.0.chars().filter(|c| !c.is_whitespace());
+        a.eq(b)
+    }
+}
+
+fn lines_equal(a: &[String], b: &[String], ignore_space: bool) -> bool {
+    if !ignore_space {
+        return a == b;
+    }
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .all(|(x, y)| SpaceInsensitive(x) == SpaceInsensitive(y))
+}
+
+fn print_diff(a: &[String], b: &[String], label: &str, ignore_space: bool) {
     println!("Diff for {}\n", label);
-    let a_joined = a.join("\n");
-    let b_joined = b.join("\n");
-    let diffs = lines(&a_joined, &b_joined);
+    let diffs: Vec<diff::Result<&str>> = if ignore_space {
+        let a: Vec<SpaceInsensitive> = a.iter().map(|l| SpaceInsensitive(l)).collect();
+        let b: Vec<SpaceInsensitive> = b.iter().map(|l| SpaceInsensitive(l)).collect();
+        diff::slice(&a, &b)
+            .into_iter()
+            .map(|d| match d {
+                diff::Result::Left(l) => diff::Result::Left(l.0),
+                diff::Result::Right(r) => diff::Result::Right(r.0),
+                diff::Result::Both(l, r) => diff::Result::Both(l.0, r.0),
+            })
+            .collect()
+    } else {
+        let a: Vec<&str> = a.iter().map(String::as_str).collect();
+        let b: Vec<&str> = b.iter().map(String::as_str).collect();
+        diff::slice(&a, &b)
+            .into_iter()
+            .map(|d| match d {
+                diff::Result::Left(l) => diff::Result::Left(*l),
+                diff::Result::Right(r) => diff::Result::Right(*r),
+                diff::Result::Both(l, r) => diff::Result::Both(*l, *r),
+            })
+            .collect()
+    };
     for d in diffs {
         match d {
             diff::Result::Left(l) => println!("- {}", l),
@@ -397,3 +1554,604 @@ fn print_diff(a: &[String], b: &[String], label: &str) {
     println!();
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(items: &[&str]) -> String {
+        items.iter().map(|l| format!("{}\n", l)).collect()
+    }
+
+    /// Twenty numbered lines, and a copy with changes at the first line,
+    /// the fifth line, and the end of the file.
+    fn numbered_pair() -> (String, String) {
+        let old: Vec<String> = (1..=20).map(|i| i.to_string()).collect();
+        let old: Vec<&str> = old.iter().map(String::as_str).collect();
+        let mut new = old.clone();
+        new[0] = "one";
+        new[4] = "five";
+        new[19] = "twenty";
+        new.push("21");
+        (lines(&old), lines(&new))
+    }
+
+    fn run(old: &str, new: &str, answers: &str) -> String {
+        let mut session = PatchSession::new(old, new);
+        session.choose(&mut answers.as_bytes()).unwrap();
+        session.synthesize()
+    }
+
+    #[test]
+    fn hunks_group_nearby_changes() {
+        let (old, new) = numbered_pair();
+        let session = PatchSession::new(&old, &new);
+        assert_eq!(session.changes.len(), 3);
+        assert_eq!(session.hunks(), vec![vec![0, 1], vec![2]]);
+
+        // Changes further apart than twice the context stay separate.
+        let old = lines(&["a", "b", "c", "d", "e", "f", "g", "h", "i"]);
+        let new = lines(&["A", "b", "c", "d", "e", "f", "g", "h", "I"]);
+        assert_eq!(PatchSession::new(&old, &new).hunks(), vec![vec![0], vec![1]]);
+        let new = lines(&["A", "b", "c", "d", "e", "f", "g", "I", "i"]);
+        assert_eq!(PatchSession::new(&old, &new).hunks(), vec![vec![0, 1]]);
+    }
+
+    #[test]
+    fn accept_and_skip_whole_hunks() {
+        let (old, new) = numbered_pair();
+        assert_eq!(run(&old, &new, "y\ny\n"), new);
+        assert_eq!(run(&old, &new, "n\nn\n"), old);
+        assert_eq!(run(&old, &new, "q\n"), old);
+        // Running out of input quits.
+        assert_eq!(run(&old, &new, ""), old);
+
+        let mut expected: Vec<String> = (1..=20).map(|i| i.to_string()).collect();
+        expected[0] = "one".into();
+        expected[4] = "five".into();
+        let expected: Vec<&str> = expected.iter().map(String::as_str).collect();
+        assert_eq!(run(&old, &new, "y\nn\n"), lines(&expected));
+        assert_eq!(run(&old, &new, "y\nq\n"), lines(&expected));
+    }
+
+    #[test]
+    fn split_then_choose_each_change() {
+        let (old, new) = numbered_pair();
+        let mut expected: Vec<String> = (1..=21).map(|i| i.to_string()).collect();
+        expected[0] = "one".into();
+        expected[19] = "twenty".into();
+        let expected: Vec<&str> = expected.iter().map(String::as_str).collect();
+        assert_eq!(run(&old, &new, "s\ny\nn\ny\n"), lines(&expected));
+
+        let mut expected: Vec<String> = (1..=20).map(|i| i.to_string()).collect();
+        expected[4] = "five".into();
+        let expected: Vec<&str> = expected.iter().map(String::as_str).collect();
+        assert_eq!(run(&old, &new, "s\nn\ny\nn\n"), lines(&expected));
+    }
+
+    #[test]
+    fn unknown_answers_prompt_again() {
+        let (old, new) = numbered_pair();
+        assert_eq!(run(&old, &new, "?\nY\n\ny\n"), new);
+        // A single-change hunk cannot be split.
+        assert_eq!(run(&old, &new, "y\ns\ny\n"), new);
+    }
+
+    #[test]
+    fn changes_at_file_boundaries() {
+        // Insertion before the first line and after a last line with no newline.
+        let old = "a\nb\nc";
+        let new = "top\na\nb\nc\nbottom\n";
+        let session = PatchSession::new(old, new);
+        assert_eq!(session.hunks(), vec![vec![0, 1]]);
+        assert_eq!(run(old, new, "s\ny\nn\n"), "top\na\nb\nc");
+        assert_eq!(run(old, new, "s\nn\ny\n"), "a\nb\nc\nbottom\n");
+        assert_eq!(run(old, new, "y\n"), new);
+
+        // Deleting everything, and starting from an empty file.
+        assert_eq!(run("x\ny\n", "", "y\n"), "");
+        assert_eq!(run("", "x\ny\n", "y\n"), "x\ny\n");
+        assert_eq!(run("", "x\ny\n", "n\n"), "");
+        assert!(PatchSession::new("same\n", "same\n").changes.is_empty());
+    }
+
+    fn glob(pat: &str, text: &str) -> bool {
+        glob_match(pat.as_bytes(), text.as_bytes())
+    }
+
+    #[test]
+    fn glob_single_segment_wildcards() {
+        assert!(glob("*.o", "main.o"));
+        assert!(glob("*.o", ".o"));
+        assert!(!glob("*.o", "src/main.o"));
+        assert!(glob("src/*.o", "src/main.o"));
+        assert!(!glob("src/*.o", "src/sub/main.o"));
+        assert!(glob("file?.txt", "file1.txt"));
+        assert!(!glob("file?.txt", "file12.txt"));
+        assert!(!glob("a?b", "a/b"));
+        assert!(glob("", ""));
+        assert!(!glob("", "a"));
+    }
+
+    #[test]
+    fn glob_double_star_only_as_a_whole_segment() {
+        assert!(glob("**/foo", "foo"));
+        assert!(glob("**/foo", "a/foo"));
+        assert!(glob("**/foo", "a/b/foo"));
+        assert!(!glob("**/foo", "a/xfoo"));
+        assert!(glob("build/**", "build/x"));
+        assert!(glob("build/**", "build/x/y.o"));
+        assert!(!glob("build/**", "build"));
+        assert!(!glob("build/**", "builder/x"));
+        assert!(glob("a/**/b", "a/b"));
+        assert!(glob("a/**/b", "a/x/b"));
+        assert!(glob("a/**/b", "a/x/y/b"));
+        assert!(!glob("a/**/b", "a/xb"));
+        assert!(!glob("a/**/b", "ab"));
+        assert!(glob("**", "any/thing"));
+
+        // Inside a segment `**` is just `*`.
+        assert!(!glob("a**b", "a/x/b"));
+        assert!(glob("a**b", "axyb"));
+        assert!(glob("a**b", "ab"));
+        assert!(!glob("**.o", "dir/main.o"));
+        assert!(glob("**.o", "main.o"));
+        assert!(!glob("x/**y", "x/a/y"));
+        assert!(glob("x/**y", "x/ay"));
+    }
+
+    fn ignored(rules: &str, path: &str, is_dir: bool) -> bool {
+        is_ignored(&parse_ignore_rules(rules), path, is_dir)
+    }
+
+    #[test]
+    fn ignore_rule_parsing() {
+        let rules = parse_ignore_rules("# comment\n\n  *.o  \n!keep.o\n/target/\ndocs/*.html\n");
+        assert_eq!(rules.len(), 4);
+        assert_eq!(rules[0].pattern, "*.o");
+        assert!(!rules[0].negate && !rules[0].dir_only && !rules[0].anchored);
+        assert!(rules[1].negate);
+        assert_eq!(rules[1].pattern, "keep.o");
+        assert_eq!(rules[2].pattern, "target");
+        assert!(rules[2].dir_only && rules[2].anchored);
+        assert!(rules[3].anchored && !rules[3].dir_only);
+    }
+
+    #[test]
+    fn ignore_rule_precedence() {
+        // Unanchored patterns match the name at any depth.
+        assert!(ignored("*.o", "main.o", false));
+        assert!(ignored("*.o", "src/deep/main.o", false));
+        assert!(!ignored("*.o", "main.c", false));
+
+        // The last matching rule wins.
+        let rules = "*.log\n!important.log\n";
+        assert!(ignored(rules, "debug.log", false));
+        assert!(!ignored(rules, "important.log", false));
+        assert!(!ignored(rules, "sub/important.log", false));
+        let rules = "!important.log\n*.log\n";
+        assert!(ignored(rules, "important.log", false));
+
+        // Directory-only rules skip files of the same name.
+        assert!(ignored("cache/", "cache", true));
+        assert!(ignored("cache/", "a/cache", true));
+        assert!(!ignored("cache/", "cache", false));
+        assert!(ignored("*\n!cache/\n", "cache", false));
+        assert!(!ignored("*\n!cache/\n", "cache", true));
+
+        // Anchored patterns only match from the repository root.
+        assert!(ignored("/target/", "target", true));
+        assert!(!ignored("/target/", "sub/target", true));
+        assert!(ignored("docs/*.html", "docs/index.html", false));
+        assert!(!ignored("docs/*.html", "src/docs/index.html", false));
+        assert!(ignored("**/gen/*.rs", "a/b/gen/x.rs", false));
+        assert!(!ignored("a**b", "a/x/b", false));
+
+        assert!(!ignored("", "anything", false));
+    }
+
+    #[test]
+    fn prune_age_parsing() {
+        assert_eq!(parse_prune_age("never").unwrap(), None);
+        assert_eq!(parse_prune_age("now").unwrap(), Some(chrono::Duration::zero()));
+        assert_eq!(parse_prune_age("all").unwrap(), Some(chrono::Duration::zero()));
+        assert_eq!(parse_prune_age("30m").unwrap(), Some(chrono::Duration::minutes(30)));
+        assert_eq!(parse_prune_age("12h").unwrap(), Some(chrono::Duration::hours(12)));
+        assert_eq!(parse_prune_age("2w").unwrap(), Some(chrono::Duration::weeks(2)));
+        assert_eq!(parse_prune_age("90").unwrap(), Some(chrono::Duration::days(90)));
+        assert_eq!(parse_prune_age(" 3.days ").unwrap(), Some(chrono::Duration::days(3)));
+        assert_eq!(parse_prune_age("45seconds").unwrap(), Some(chrono::Duration::seconds(45)));
+        assert_eq!(parse_prune_age("1week").unwrap(), Some(chrono::Duration::weeks(1)));
+        for bad in ["", "w", "2y", "-1d", "2 w", "soon", "1.5h"] {
+            assert!(parse_prune_age(bad).is_err(), "{:?}", bad);
+        }
+    }
+
+    #[test]
+    fn huge_prune_ages_are_errors_not_panics() {
+        let err = parse_prune_age("99999999999999w").unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{}", err);
+        assert!(parse_prune_age("9223372036854775807s").is_err());
+
+        // Parses as a duration, but reaches back past the earliest date.
+        let age = parse_prune_age("1000000000d").unwrap().unwrap();
+        let err = prune_cutoff(Utc::now(), age).unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{}", err);
+        let now = Utc::now();
+        let week = chrono::Duration::weeks(1);
+        assert_eq!(prune_cutoff(now, week).unwrap(), now - week);
+    }
+
+    /// A fresh repository in its own temporary directory.
+    struct TempRepo(PathBuf);
+
+    impl TempRepo {
+        fn new(name: &str) -> TempRepo {
+            let name = format!("scm-test-{}-{}", std::process::id(), name);
+            let dir = std::env::temp_dir().join(name);
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            init_repo(&dir).unwrap();
+            TempRepo(dir)
+        }
+    }
+
+    impl Drop for TempRepo {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn commit_blob(repo: &Path, content: &str, parent: Option<String>) -> (String, String) {
+        let blob = store_blob(repo, content.as_bytes()).unwrap();
+        let commit = Commit {
+            tree: BTreeMap::from([("file.txt".to_string(), blob.clone())]),
+            parent,
+            message: content.to_string(),
+            timestamp: Utc::now(),
+        };
+        (store_commit(repo, &commit).unwrap(), blob)
+    }
+
+    fn backdate(path: &Path, age: chrono::Duration) {
+        let when = std::time::SystemTime::now() - age.to_std().unwrap();
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(when)
+            .unwrap();
+    }
+
+    #[test]
+    fn gc_keeps_objects_reachable_from_the_reflog() {
+        let tmp = TempRepo::new("gc");
+        let repo = tmp.0.as_path();
+        let month = chrono::Duration::days(30);
+        let (c1, b1) = commit_blob(repo, "one", None);
+        write_head(repo, &c1, "commit: one").unwrap();
+        let (c2, b2) = commit_blob(repo, "two", Some(c1.clone()));
+        write_head(repo, &c2, "commit: two").unwrap();
+        write_head(repo, &c1, "revert: two").unwrap();
+        let orphan = store_blob(repo, b"orphan").unwrap();
+        let staged = store_blob(repo, b"staged").unwrap();
+        write_index(
+            repo,
+            &[IndexEntry {
+                path: "file.txt".to_string(),
+                blob: Some(staged.clone()),
+            }],
+        )
+        .unwrap();
+        for h in [&c1, &c2] {
+            backdate(&repo.join(COMMITS_DIR).join(h), month);
+        }
+        for h in [&b1, &b2, &orphan, &staged] {
+            backdate(&repo.join(OBJECTS_DIR).join(h), month);
+        }
+        let commit_exists = |h: &str| repo.join(COMMITS_DIR).join(h).exists();
+        let blob_exists = |h: &str| repo.join(OBJECTS_DIR).join(h).exists();
+
+        // The reverted commit is only reachable from recent reflog entries.
+        let report = gc(repo, Utc::now() - chrono::Duration::days(1)).unwrap();
+        assert_eq!(
+            report,
+            GcReport {
+                commits: 0,
+                blobs: 1,
+                reflog_entries: 0
+            }
+        );
+        assert!(commit_exists(&c1) && commit_exists(&c2));
+        assert!(blob_exists(&b1) && blob_exists(&b2) && blob_exists(&staged));
+        assert!(!blob_exists(&orphan));
+        assert_eq!(load_reflog(repo).unwrap().len(), 3);
+
+        // Once the reflog entries expire, only HEAD and the index keep objects.
+        let report = gc(repo, Utc::now() + chrono::Duration::seconds(1)).unwrap();
+        assert_eq!(
+            report,
+            GcReport {
+                commits: 1,
+                blobs: 1,
+                reflog_entries: 3
+            }
+        );
+        assert!(commit_exists(&c1) && !commit_exists(&c2));
+        assert!(blob_exists(&b1) && !blob_exists(&b2) && blob_exists(&staged));
+        assert!(load_reflog(repo).unwrap().is_empty());
+        assert_eq!(read_head(repo).unwrap(), Some(c1));
+    }
+
+    #[test]
+    fn gc_spares_recent_unreachable_objects() {
+        let tmp = TempRepo::new("gc-recent");
+        let repo = tmp.0.as_path();
+        let orphan = store_blob(repo, b"fresh").unwrap();
+        let report = gc(repo, Utc::now() - chrono::Duration::weeks(2)).unwrap();
+        assert_eq!(report.blobs, 0);
+        assert!(repo.join(OBJECTS_DIR).join(&orphan).exists());
+    }
+
+    fn write_files(dir: &Path, files: &[(&str, &str)]) {
+        for (rel, content) in files {
+            let p = dir.join(rel);
+            fs::create_dir_all(p.parent().unwrap()).unwrap();
+            fs::write(p, content).unwrap();
+        }
+    }
+
+    fn snapshot(dir: PathBuf, day: &str) -> Snapshot {
+        Snapshot {
+            message: default_snapshot_message(&dir),
+            timestamp: parse_snapshot_date(day).unwrap(),
+            dir,
+        }
+    }
+
+    #[test]
+    fn status_shows_staged_then_deleted_files() {
+        let tmp = TempRepo::new("status");
+        let repo = tmp.0.as_path();
+        let attributes = load_attributes(repo).unwrap();
+        let (_, blob) = commit_blob(repo, "one", None);
+        let tree = BTreeMap::from([("file.txt".to_string(), blob)]);
+        write_files(repo, &[("file.txt", "two"), ("new.txt", "new")]);
+        add_file(repo, "file.txt").unwrap();
+        add_file(repo, "new.txt").unwrap();
+        let staged = load_index(repo).unwrap();
+        let codes = short_status_codes(repo, &attributes, &staged, &tree).unwrap();
+        assert_eq!(
+            codes,
+            BTreeMap::from([
+                ("file.txt".to_string(), ('M', ' ')),
+                ("new.txt".to_string(), ('A', ' ')),
+            ])
+        );
+
+        // Deleting after `add` stages the deletion, not a modification.
+        fs::remove_file(repo.join("file.txt")).unwrap();
+        fs::remove_file(repo.join("new.txt")).unwrap();
+        let codes = short_status_codes(repo, &attributes, &staged, &tree).unwrap();
+        assert_eq!(
+            codes,
+            BTreeMap::from([
+                ("file.txt".to_string(), ('D', ' ')),
+                ("new.txt".to_string(), ('A', 'D')),
+            ])
+        );
+
+        // Unstaged deletions still land in the second column.
+        let codes = short_status_codes(repo, &attributes, &[], &tree).unwrap();
+        assert_eq!(codes, BTreeMap::from([("file.txt".to_string(), (' ', 'D'))]));
+    }
+
+    #[test]
+    fn add_patch_refuses_a_fully_staged_path() {
+        let tmp = TempRepo::new("add-patch");
+        let repo = tmp.0.as_path();
+        let (old, new) = numbered_pair();
+        fs::write(repo.join("f.txt"), &old).unwrap();
+        add_patch(repo, "f.txt", &mut "y\n".as_bytes()).unwrap();
+        let staged = load_index(repo).unwrap()[0].blob.clone().unwrap();
+        assert_eq!(load_blob(repo, &staged).unwrap(), old.as_bytes());
+
+        // Hunks can still be added on top of a partial stage.
+        fs::write(repo.join("f.txt"), &new).unwrap();
+        add_patch(repo, "f.txt", &mut "y\nn\nn\n".as_bytes()).unwrap();
+        let partial = load_index(repo).unwrap()[0].blob.clone().unwrap();
+        assert_ne!(partial, staged);
+
+        // After `add f`, `add -p f` must not replace the whole-file stage.
+        add_file(repo, "f.txt").unwrap();
+        let err = add_patch(repo, "f.txt", &mut "y\n".as_bytes()).unwrap_err();
+        assert!(err.to_string().contains("already fully staged"), "{}", err);
+        let index = load_index(repo).unwrap();
+        assert_eq!(
+            index,
+            vec![IndexEntry {
+                path: "f.txt".to_string(),
+                blob: None
+            }]
+        );
+    }
+
+    #[test]
+    fn manifest_columns_may_be_padded() {
+        let tmp = TempRepo::new("manifest");
+        let path = tmp.0.join("manifest.txt");
+        let text = "# dir  date  message\n\
+                    v1   2020-01-01  first   import \n\
+                    v2\t\t2020-02-01\tsecond\n\
+                    \t v3 2020-03-01T12:00:00Z\n\
+                    v4 2020-04-01 \t \n";
+        fs::write(&path, text).unwrap();
+        let snaps = load_manifest(&path).unwrap();
+        let got: Vec<(PathBuf, String, String)> = snaps
+            .iter()
+            .map(|s| (s.dir.clone(), s.timestamp.to_rfc3339(), s.message.clone()))
+            .collect();
+        assert_eq!(
+            got,
+            vec![
+                (tmp.0.join("v1"), "2020-01-01T00:00:00+00:00".into(), "first   import".into()),
+                (tmp.0.join("v2"), "2020-02-01T00:00:00+00:00".into(), "second".into()),
+                (tmp.0.join("v3"), "2020-03-01T12:00:00+00:00".into(), "Import snapshot v3".into()),
+                (tmp.0.join("v4"), "2020-04-01T00:00:00+00:00".into(), "Import snapshot v4".into()),
+            ]
+        );
+
+        fs::write(&path, "v1   \n").unwrap();
+        let err = load_manifest(&path).err().unwrap();
+        assert!(err.to_string().contains("missing date"), "{}", err);
+    }
+
+    #[test]
+    fn import_snapshots_checks_out_the_last_tree() {
+        let tmp = TempRepo::new("import");
+        let repo = tmp.0.join("work");
+        fs::create_dir(&repo).unwrap();
+        init_repo(&repo).unwrap();
+        let (v1, v2) = (tmp.0.join("v1"), tmp.0.join("v2"));
+        write_files(&v1, &[("a.txt", "a1\n"), ("b.txt", "b\n")]);
+        write_files(&v2, &[("a.txt", "a2\n"), ("c/d.txt", "d\n")]);
+
+        let snaps = [snapshot(v1, "2020-01-01"), snapshot(v2, "2021-06-30")];
+        let hashes = import_snapshots(&repo, &snaps).unwrap();
+        assert_eq!(hashes.len(), 2);
+        assert_eq!(read_head(&repo).unwrap().as_ref(), hashes.last());
+        let last = load_commit(&repo, &hashes[1]).unwrap();
+        assert_eq!(last.parent.as_ref(), Some(&hashes[0]));
+        assert_eq!(last.message, "Import snapshot v2");
+        assert_eq!(last.timestamp.to_rfc3339(), "2021-06-30T00:00:00+00:00");
+
+        // The working copy matches the imported tree, so nothing shows as changed.
+        assert_eq!(fs::read_to_string(repo.join("a.txt")).unwrap(), "a2\n");
+        assert_eq!(fs::read_to_string(repo.join("c/d.txt")).unwrap(), "d\n");
+        let attributes = load_attributes(&repo).unwrap();
+        for (path, blob_hash) in &last.tree {
+            let hash = file_content_hash(&repo, &attributes, path).unwrap();
+            assert_eq!(hash.as_ref(), Some(blob_hash));
+        }
+        let (mut untracked, mut ignored) = (vec![], vec![]);
+        let tracked = last.tree.keys().cloned().collect();
+        walk_untracked(&repo, &repo, &[], &tracked, &mut untracked, &mut ignored).unwrap();
+        assert_eq!(untracked, Vec::<String>::new());
+
+        // A later import removes files the new tree no longer has.
+        let v3 = tmp.0.join("v3");
+        write_files(&v3, &[("c/d.txt", "d\n")]);
+        import_snapshots(&repo, &[snapshot(v3, "2022-01-01")]).unwrap();
+        assert!(!repo.join("a.txt").exists());
+        assert!(repo.join("c/d.txt").exists());
+    }
+
+    #[test]
+    fn import_snapshots_refuses_a_dirty_worktree() {
+        let tmp = TempRepo::new("import-dirty");
+        let repo = tmp.0.join("work");
+        fs::create_dir(&repo).unwrap();
+        init_repo(&repo).unwrap();
+        let v1 = tmp.0.join("v1");
+        write_files(&v1, &[("a.txt", "a1\n")]);
+        import_snapshots(&repo, &[snapshot(v1, "2020-01-01")]).unwrap();
+        let head = read_head(&repo).unwrap();
+
+        let v2 = tmp.0.join("v2");
+        write_files(&v2, &[("a.txt", "a2\n"), ("new.txt", "new\n")]);
+        let snaps = [snapshot(v2, "2021-01-01")];
+
+        fs::write(repo.join("a.txt"), "edited\n").unwrap();
+        let err = import_snapshots(&repo, &snaps).unwrap_err();
+        assert!(err.to_string().contains("a.txt has local changes"));
+        fs::remove_file(repo.join("a.txt")).unwrap();
+        assert!(import_snapshots(&repo, &snaps).is_err());
+        fs::write(repo.join("a.txt"), "a1\n").unwrap();
+
+        fs::write(repo.join("new.txt"), "mine\n").unwrap();
+        let err = import_snapshots(&repo, &snaps).unwrap_err();
+        assert!(err.to_string().contains("new.txt would be overwritten"));
+        fs::remove_file(repo.join("new.txt")).unwrap();
+
+        let staged = IndexEntry {
+            path: "a.txt".to_string(),
+            blob: None,
+        };
+        write_index(&repo, &[staged]).unwrap();
+        assert!(import_snapshots(&repo, &snaps).is_err());
+        assert_eq!(read_head(&repo).unwrap(), head);
+
+        write_index(&repo, &[]).unwrap();
+        import_snapshots(&repo, &snaps).unwrap();
+        assert_eq!(fs::read_to_string(repo.join("new.txt")).unwrap(), "new\n");
+    }
+
+    fn attrs(rules: &str, path: &str) -> PathAttrs {
+        parse_attributes(rules).for_path(path)
+    }
+
+    #[test]
+    fn attributes_resolve_per_path() {
+        let rules = "* text=auto\n*.bat eol=crlf\n*.png -text\ndocs/*.md whitespace=fix\n";
+        let a = attrs(rules, "src/main.rs");
+        assert_eq!((a.text, a.eol, a.fix_whitespace), (TextAttr::Auto, None, false));
+        let a = attrs(rules, "tools/run.bat");
+        assert_eq!((a.text, a.eol), (TextAttr::Auto, Some(Eol::Crlf)));
+        assert_eq!(attrs(rules, "logo.png").text, TextAttr::Binary);
+        assert!(attrs(rules, "docs/guide.md").fix_whitespace);
+        assert!(!attrs(rules, "guide.md").fix_whitespace);
+        // Later lines override earlier ones.
+        let a = attrs("*.txt eol=crlf\nplain.txt eol=lf\n", "plain.txt");
+        assert_eq!(a.eol, Some(Eol::Lf));
+        assert_eq!(attrs("", "x").text, TextAttr::Unspecified);
+    }
+
+    #[test]
+    fn clean_and_smudge_round_trip() {
+        let mixed: &[u8] = b"one\r\ntwo\nthree\r\nlast";
+
+        // `text`: LF in the repository, left as LF on checkout.
+        let a = attrs("* text", "f");
+        assert_eq!(a.clean(mixed), b"one\ntwo\nthree\nlast");
+        assert_eq!(a.smudge(&a.clean(mixed)), b"one\ntwo\nthree\nlast");
+
+        // `-text`: bytes pass through untouched both ways.
+        let a = attrs("* -text", "f");
+        assert_eq!(a.clean(mixed), mixed);
+        assert_eq!(a.smudge(mixed), mixed);
+
+        // `eol=lf` implies text and checks out LF.
+        let a = attrs("* eol=lf", "f");
+        assert_eq!(a.clean(mixed), b"one\ntwo\nthree\nlast");
+        assert_eq!(a.smudge(b"x\ny\n"), b"x\ny\n");
+
+        // `eol=crlf` stores LF and checks out CRLF.
+        let a = attrs("* eol=crlf", "f");
+        let stored = a.clean(mixed);
+        assert_eq!(stored, b"one\ntwo\nthree\nlast");
+        let checked_out = a.smudge(&stored);
+        assert_eq!(checked_out, b"one\r\ntwo\r\nthree\r\nlast");
+        assert_eq!(a.clean(&checked_out), stored);
+        assert_eq!(a.smudge(b"\n\n"), b"\r\n\r\n");
+    }
+
+    #[test]
+    fn smudge_leaves_existing_crlf_alone() {
+        // A blob committed before the attribute existed may hold CRLF.
+        let a = attrs("* eol=crlf", "f");
+        assert_eq!(a.smudge(b"a\r\nb\nc\r\n"), b"a\r\nb\r\nc\r\n");
+        assert_eq!(a.smudge(b"\r\n"), b"\r\n");
+        assert_eq!(a.smudge(b"\r\r\n"), b"\r\r\n");
+        // Binary content is never converted.
+        let a = attrs("* text=auto eol=crlf", "f");
+        assert_eq!(a.smudge(b"bin\0ary\n"), b"bin\0ary\n");
+        assert_eq!(a.smudge(b"txt\n"), b"txt\r\n");
+    }
+
+    #[test]
+    fn whitespace_fix_strips_trailing_blanks() {
+        let a = attrs("* text whitespace=fix", "f");
+        assert_eq!(a.clean(b"a  \r\nb\t\n  \nc "), b"a\nb\n\nc");
+        let a = attrs("* text whitespace=fix\nkeep -whitespace\n", "keep");
+        assert_eq!(a.clean(b"a  \n"), b"a  \n");
+    }
+}